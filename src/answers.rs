@@ -0,0 +1,198 @@
+use crate::puzzle::PartError;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+pub const ANSWERS_PATH: &str = "src/answers.toml";
+
+/// Known-correct answers for each day and part, read from a small subset of TOML:
+///
+/// ```toml
+/// [day01]
+/// part1 = "3352674"
+/// part2 = '''
+/// multi-line answers go
+/// between triple quotes'''
+/// ```
+pub struct Answers {
+    expected: HashMap<(u8, u8), String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    /// The answer differs from the known-correct one
+    Regression { expected: String },
+    /// No answer was produced
+    Fail { message: String },
+    /// There's no known-correct answer to compare against
+    Unverified,
+}
+
+impl Answers {
+    pub fn empty() -> Self {
+        Answers { expected: HashMap::new() }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(x) => x,
+            Err(error) => return Err(format!("Could not read {}: {}", path, error)),
+        };
+
+        Answers::parse(&content).map_err(|message| format!("{}: {}", path, message))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut expected = HashMap::new();
+        let mut day = None;
+        let mut lines = content.lines().enumerate();
+
+        while let Some((index, raw_line)) = lines.next() {
+            let line_number = index + 1;
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') {
+                if !line.ends_with(']') || !line.starts_with("[day") {
+                    return Err(format!("Line {}: expected a [dayNN] table, found '{}'", line_number, line));
+                }
+
+                day = match line[4..(line.len() - 1)].parse::<u8>() {
+                    Ok(x) => Some(x),
+                    Err(_) => return Err(format!("Line {}: invalid day in '{}'", line_number, line)),
+                };
+
+                continue;
+            }
+
+            let (key, value) = match line.find('=') {
+                None => return Err(format!("Line {}: expected 'partN = \"answer\"', found '{}'", line_number, line)),
+                Some(x) => (line[..x].trim(), line[(x + 1)..].trim()),
+            };
+
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                x => return Err(format!("Line {}: unknown key '{}'", line_number, x)),
+            };
+
+            let day = match day {
+                None => return Err(format!("Line {}: answer given before any [dayNN] table", line_number)),
+                Some(x) => x,
+            };
+
+            let answer = if let Some(rest) = value.strip_prefix("'''") {
+                // Multi-line literal, where a newline straight after the opening quotes is skipped
+                let mut answer = rest.to_owned();
+                let mut is_first_line = rest.is_empty();
+                loop {
+                    if let Some(end) = answer.find("'''") {
+                        answer.truncate(end);
+                        break;
+                    }
+
+                    match lines.next() {
+                        None => return Err(format!("Line {}: unterminated multi-line answer", line_number)),
+                        Some((_, next_line)) => {
+                            if !is_first_line {
+                                answer.push('\n');
+                            }
+
+                            answer.push_str(next_line);
+                            is_first_line = false;
+                        }
+                    }
+                }
+
+                answer
+            } else if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+                value[1..(value.len() - 1)].to_owned()
+            } else {
+                return Err(format!("Line {}: answers must be quoted, found '{}'", line_number, value));
+            };
+
+            expected.insert((day, part), answer);
+        }
+
+        Ok(Answers { expected })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.expected.get(&(day, part)).map(|x| x.as_str())
+    }
+
+    /// A part that doesn't apply to its input is only a failure when there's a known answer for it
+    pub fn check(&self, day: u8, part: u8, answer: &Result<String, PartError>) -> Verdict {
+        let answer = match (answer, self.get(day, part)) {
+            (Err(PartError::NotApplicable(_)), None) => return Verdict::Unverified,
+            (Err(PartError::Panic(message)), _) | (Err(PartError::NotApplicable(message)), _) => {
+                return Verdict::Fail { message: message.clone() }
+            }
+
+            (Ok(x), _) => x,
+        };
+
+        match self.get(day, part) {
+            None => Verdict::Unverified,
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Regression { expected: expected.to_owned() },
+        }
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Regression { .. } => write!(f, "REGRESSION"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Unverified => write!(f, "unverified"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_and_multi_line_answers() {
+        let answers = Answers::parse("# Known answers
+[day01]
+part1 = \"42\"
+
+[day08]
+part2 = '''
+#  #
+ ## '''
+").unwrap();
+
+        assert_eq!(answers.get(1, 1), Some("42"));
+        assert_eq!(answers.get(1, 2), None);
+        assert_eq!(answers.get(8, 2), Some("#  #\n ## "));
+    }
+
+    #[test]
+    fn reports_line_of_invalid_entries() {
+        assert_eq!(Answers::parse("[day01]\npart3 = \"1\"").err(), Some("Line 2: unknown key 'part3'".to_owned()));
+        assert_eq!(Answers::parse("part1 = \"1\"").err(),
+                   Some("Line 1: answer given before any [dayNN] table".to_owned()));
+    }
+
+    #[test]
+    fn verdicts() {
+        let answers = Answers::parse("[day02]\npart1 = \"11590668\"").unwrap();
+
+        assert_eq!(answers.check(2, 1, &Ok("11590668".to_owned())), Verdict::Pass);
+        assert_eq!(answers.check(2, 1, &Ok("1".to_owned())), Verdict::Regression { expected: "11590668".to_owned() });
+        assert_eq!(answers.check(2, 1, &Err(PartError::Panic("boom".to_owned()))), Verdict::Fail { message: "boom".to_owned() });
+        assert_eq!(answers.check(2, 2, &Ok("1".to_owned())), Verdict::Unverified);
+
+        let skipped = Err(PartError::NotApplicable("No intersections".to_owned()));
+        assert_eq!(answers.check(2, 2, &skipped), Verdict::Unverified);
+        assert_eq!(answers.check(2, 1, &skipped), Verdict::Fail { message: "No intersections".to_owned() });
+    }
+}
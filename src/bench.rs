@@ -0,0 +1,304 @@
+use crate::puzzle::{self, PartError, Puzzle};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Used when no other baseline is given.  Baselines are machine specific, so this is ignored by
+/// git, and it's outside target/ so `cargo clean` leaves it alone.
+pub const BASELINE_PATH: &str = "bench/baseline.txt";
+
+/// How much slower than the baseline a stage's median can be before it's a regression
+pub const REGRESSION_THRESHOLD: f64 = 0.2;
+
+/// Differences smaller than this are treated as noise, as fast stages vary a lot in relative terms
+const NOISE_MS: f64 = 0.5;
+
+/// Every timing sample taken for each stage, or why the stage couldn't be timed
+pub type Samples = Vec<(Stage, Result<Vec<Duration>, PartError>)>;
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Stage { Parse, Part1, Part2 }
+
+/// Timing statistics in milliseconds
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stats {
+    pub mean: f64,
+    pub median: f64,
+    pub stddev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Comparison {
+    NoBaseline,
+    Unchanged { baseline: f64 },
+    Improved { baseline: f64 },
+    Regressed { baseline: f64 },
+}
+
+/// Median milliseconds for each day and stage from an earlier benchmark run
+pub struct Baseline {
+    medians: HashMap<(u8, Stage), f64>,
+}
+
+pub const STAGES: [Stage; 3] = [Stage::Parse, Stage::Part1, Stage::Part2];
+
+impl Stage {
+    fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Part1 => "part1",
+            Stage::Part2 => "part2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Stage> {
+        STAGES.iter().find(|x| x.name() == name).cloned()
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            panic!("At least one sample is needed to calculate statistics");
+        }
+
+        let mut values: Vec<f64> = samples.iter().map(|x| x.as_secs_f64() * 1000.0).collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+        let middle = values.len() / 2;
+        let median = if values.len().is_multiple_of(2) {
+            (values[middle - 1] + values[middle]) / 2.0
+        } else {
+            values[middle]
+        };
+
+        Stats {
+            mean,
+            median,
+            stddev: variance.sqrt(),
+            min: values[0],
+            max: values[values.len() - 1],
+        }
+    }
+}
+
+impl Baseline {
+    pub fn empty() -> Self {
+        Baseline { medians: HashMap::new() }
+    }
+
+    /// Loads the baseline if one has been saved
+    pub fn load(path: &str) -> Result<Self, String> {
+        if !Path::new(path).exists() {
+            return Ok(Baseline::empty());
+        }
+
+        let content = fs::read_to_string(path).map_err(|error| format!("Could not read {}: {}", path, error))?;
+        Baseline::parse(&content).map_err(|message| format!("{}: {}", path, message))
+    }
+
+    /// Parses lines of `<day> <stage> <median ms>`, ignoring blank lines and `#` comments
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut medians = HashMap::new();
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let parsed = match fields.as_slice() {
+                [day, stage, median] => (day.parse::<u8>().ok(), Stage::from_name(stage), median.parse::<f64>().ok()),
+                _ => (None, None, None),
+            };
+
+            match parsed {
+                (Some(day), Some(stage), Some(median)) => { medians.insert((day, stage), median); }
+                _ => return Err(format!("Line {}: expected '<day> <stage> <median ms>', found '{}'", index + 1, line)),
+            }
+        }
+
+        Ok(Baseline { medians })
+    }
+
+    pub fn set(&mut self, day: u8, stage: Stage, median: f64) {
+        self.medians.insert((day, stage), median);
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        if let Some(directory) = Path::new(path).parent() {
+            fs::create_dir_all(directory).map_err(|error| format!("Could not create {}: {}", directory.display(), error))?;
+        }
+
+        fs::write(path, self.render()).map_err(|error| format!("Could not write {}: {}", path, error))
+    }
+
+    pub fn render(&self) -> String {
+        let mut entries: Vec<(&(u8, Stage), &f64)> = self.medians.iter().collect();
+        entries.sort_by_key(|((day, stage), _)| (*day, STAGES.iter().position(|x| x == stage)));
+
+        let mut result = String::from("# Median milliseconds per day and stage\n");
+        for ((day, stage), median) in entries {
+            result.push_str(&format!("{:02} {} {:.4}\n", day, stage, median));
+        }
+
+        result
+    }
+
+    pub fn compare(&self, day: u8, stage: Stage, stats: &Stats) -> Comparison {
+        let baseline = match self.medians.get(&(day, stage)) {
+            None => return Comparison::NoBaseline,
+            Some(x) => *x,
+        };
+
+        let difference = stats.median - baseline;
+        if difference.abs() < NOISE_MS {
+            Comparison::Unchanged { baseline }
+        } else if difference > baseline * REGRESSION_THRESHOLD {
+            Comparison::Regressed { baseline }
+        } else if -difference > baseline * REGRESSION_THRESHOLD {
+            Comparison::Improved { baseline }
+        } else {
+            Comparison::Unchanged { baseline }
+        }
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (label, baseline) = match self {
+            Comparison::NoBaseline => return write!(f, "no baseline"),
+            Comparison::Unchanged { baseline } => ("unchanged", baseline),
+            Comparison::Improved { baseline } => ("improved", baseline),
+            Comparison::Regressed { baseline } => ("REGRESSION", baseline),
+        };
+
+        write!(f, "{} (baseline {:.3}ms)", label, baseline)
+    }
+}
+
+/// Times parsing and each part `iterations` times.  The parts are solved from a single parsed
+/// input, so only the part itself is timed.  A stage that panics isn't run again, and the parts
+/// aren't run at all when parsing panics.
+pub fn measure<P: Puzzle>(input: &str, iterations: usize) -> Samples {
+    let (parse_samples, parsed) = match time_stage(iterations, || puzzle::parse::<P>(input)) {
+        Ok(x) => x,
+        Err(error) => return STAGES.iter().map(|stage| (*stage, Err(error.clone()))).collect(),
+    };
+
+    let part1 = time_stage(iterations, || puzzle::catch(|| P::part1(&parsed).to_string()));
+    let part2 = time_stage(iterations, || puzzle::catch(|| P::part2(&parsed).to_string()));
+    vec![
+        (Stage::Parse, Ok(parse_samples)),
+        (Stage::Part1, part1.map(|(samples, _)| samples)),
+        (Stage::Part2, part2.map(|(samples, _)| samples)),
+    ]
+}
+
+/// Runs the stage `iterations` times, returning the time each run took and the last result
+fn time_stage<T, F>(iterations: usize, mut stage: F) -> Result<(Vec<Duration>, T), PartError>
+    where F: FnMut() -> Result<T, PartError> {
+    let mut samples = Vec::with_capacity(iterations);
+    let mut last = None;
+    for _ in 0..iterations {
+        let now = Instant::now();
+        last = Some(stage()?);
+        samples.push(now.elapsed());
+    }
+
+    match last {
+        None => panic!("At least one iteration is needed to time a stage"),
+        Some(x) => Ok((samples, x)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|x| Duration::from_millis(*x)).collect()
+    }
+
+    #[test]
+    fn calculates_statistics() {
+        let stats = Stats::from_samples(&millis(&[4, 2, 8, 6]));
+
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!(stats.median, 5.0);
+        assert_eq!(stats.stddev, 5_f64.sqrt());
+        assert_eq!((stats.min, stats.max), (2.0, 8.0));
+        assert_eq!(Stats::from_samples(&millis(&[3, 1, 2])).median, 2.0);
+    }
+
+    #[test]
+    fn baseline_round_trips() {
+        let mut baseline = Baseline::empty();
+        baseline.set(16, Stage::Part2, 486.25);
+        baseline.set(1, Stage::Parse, 0.01);
+
+        let parsed = Baseline::parse(&baseline.render()).unwrap();
+        assert_eq!(parsed.render(), "# Median milliseconds per day and stage\n01 parse 0.0100\n16 part2 486.2500\n");
+        assert!(Baseline::parse("1 part3 2.0").is_err());
+    }
+
+    #[test]
+    fn compares_against_baseline() {
+        let mut baseline = Baseline::empty();
+        baseline.set(3, Stage::Part1, 10.0);
+        baseline.set(3, Stage::Part2, 0.1);
+
+        let stats = |median| Stats { mean: median, median, stddev: 0.0, min: median, max: median };
+        assert_eq!(baseline.compare(3, Stage::Part1, &stats(15.0)), Comparison::Regressed { baseline: 10.0 });
+        assert_eq!(baseline.compare(3, Stage::Part1, &stats(11.0)), Comparison::Unchanged { baseline: 10.0 });
+        assert_eq!(baseline.compare(3, Stage::Part1, &stats(5.0)), Comparison::Improved { baseline: 10.0 });
+        assert_eq!(baseline.compare(3, Stage::Part2, &stats(0.3)), Comparison::Unchanged { baseline: 0.1 });
+        assert_eq!(baseline.compare(4, Stage::Part1, &stats(1.0)), Comparison::NoBaseline);
+    }
+
+    struct Unfinished;
+
+    impl Puzzle for Unfinished {
+        type Input = u32;
+        type Part1 = u32;
+        type Part2 = u32;
+
+        fn parse(input: &str) -> u32 {
+            input.parse().unwrap()
+        }
+
+        fn part1(_: &u32) -> u32 {
+            puzzle::not_applicable("No part 1 for this input")
+        }
+
+        fn part2(value: &u32) -> u32 {
+            panic!("Part 2 of {} isn't solved yet", value)
+        }
+    }
+
+    #[test]
+    fn measures_around_failed_stages() {
+        let samples = measure::<Unfinished>("7", 3);
+        assert_eq!(samples[0].0, Stage::Parse);
+        assert_eq!(samples[0].1.as_ref().map(|x| x.len()), Ok(3));
+        assert_eq!(samples[1].1, Err(PartError::NotApplicable("No part 1 for this input".to_owned())));
+        assert_eq!(samples[2].1, Err(PartError::Panic("Part 2 of 7 isn't solved yet".to_owned())));
+
+        let samples = measure::<Unfinished>("seven", 3);
+        assert_eq!(samples.len(), 3);
+        assert!(samples.iter().all(|(_, x)| matches!(x, Err(PartError::Panic(message)) if message.starts_with("Parsing failed"))));
+    }
+}
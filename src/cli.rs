@@ -0,0 +1,652 @@
+use crate::bench;
+use crate::image::ImageFormat;
+use crate::input::InputSource;
+use crate::puzzle_03::Metric;
+use crate::puzzle_04;
+
+pub const USAGE: &str = "Usage:
+    aoc2019 list                                    Lists the days that have solutions
+    aoc2019 run --day <day> [--part <part>] [--input <path> | --input-text <text>] [--format <format>]
+                                                    Runs a single day, optionally on another
+                                                    input (use '--input -' to read from stdin)
+    aoc2019 run --all [--format <format>]           Runs every day, timing each one
+    aoc2019 bench (--day <day> | --all) [--iterations <count>] [--baseline <path>] [--save-baseline]
+                                                    Times each day's parsing and parts over many
+                                                    runs, comparing them to the saved baseline
+                                                    (bench/baseline.txt unless a path is given)
+    aoc2019 image --day <day> --output <path> [--scale <scale>] [--input <path> | --input-text <text>]
+                                                    Draws the day's result as a .ppm, .png or .svg
+                                                    image, for days 8, 11, 17 and 19
+    aoc2019 sif encode --input <path> --output <path> [--layers <count>] [--seed <seed>]
+                                                    Converts a .png or .pbm image into Space Image
+                                                    Format digits, split over random layers
+    aoc2019 sif decode --input <path> --width <width> --height <height> --output <path> [--scale <scale>]
+                                                    Flattens Space Image Format digits into a
+                                                    .ppm, .png or .svg image
+    aoc2019 wires [--input <path> | --input-text <text>] [--min-wires <count>] [--metric <metric>] [--svg <path>]
+                                                    Lists where day 3's wires cross, closest first
+                                                    by 'manhattan' (the default) or 'walk' distance,
+                                                    optionally drawing them as an SVG image
+    aoc2019 passwords --rules <rules> --range <min-max> [--limit <count>]
+                                                    Counts and lists the numbers in the range that
+                                                    pass rules like 'monotonic nondecreasing;
+                                                    run==2 exists; run>=3 exists; run<=4 all;
+                                                    length 6'
+    aoc2019 breakout [--watch | --keyboard | --compare | --record | --memory | --wall]
+                                                    Plays day 13's game and prints the final score,
+                                                    or watches it, plays it from the keyboard,
+                                                    compares the controllers, records it to
+                                                    breakout.cast and breakout.gif, prints where
+                                                    its state is in memory, or wins it by turning
+                                                    the paddle's row into a wall
+
+Answers are checked against the known answers in src/answers.toml.  The run format is either
+'text' (the default) or 'json', which prints one object per part or error";
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    List,
+    Run(RunOptions),
+    Bench(BenchOptions),
+    Image(ImageOptions),
+    Sif(SifOptions),
+    Wires(WiresOptions),
+    Passwords(PasswordOptions),
+    Breakout(BreakoutMode),
+    Help,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum DaySelection {
+    Single(u8),
+    All,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum OutputFormat { Text, Json }
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RunOptions {
+    pub days: DaySelection,
+    pub part: Option<u8>,
+    /// Replaces the day's own input when set
+    pub input: Option<InputSource>,
+    pub format: OutputFormat,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct BenchOptions {
+    pub days: DaySelection,
+    pub iterations: usize,
+    /// Where the baseline is read from and saved to
+    pub baseline: String,
+    /// Replaces the stored baseline with this run's timings
+    pub save_baseline: bool,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct ImageOptions {
+    pub day: u8,
+    pub output: String,
+    /// Taken from the output's extension
+    pub format: ImageFormat,
+    /// Width and height of the square each cell is drawn as
+    pub scale: usize,
+    pub input: Option<InputSource>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum SifConversion {
+    /// From an image, spreading its pixels over `layers` layers picked by `seed`
+    Encode { layers: usize, seed: u64 },
+    /// Into an image, with the format taken from the output's extension
+    Decode { width: usize, height: usize, format: ImageFormat, scale: usize },
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct SifOptions {
+    pub conversion: SifConversion,
+    pub input: String,
+    pub output: String,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct WiresOptions {
+    /// Day 3's own input when not set
+    pub input: Option<InputSource>,
+    /// Points at least this many wires cross are listed separately
+    pub min_wires: usize,
+    pub metric: Metric,
+    /// Where to draw the wires, if anywhere
+    pub svg: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct PasswordOptions {
+    /// Statements for `RuleSet::parse`, checked when the command runs
+    pub rules: String,
+    pub min: u64,
+    pub max: u64,
+    /// How many of the passwords to list
+    pub limit: usize,
+}
+
+/// How to play day 13's breakout game
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum BreakoutMode {
+    /// Without a screen, printing the final score
+    Headless,
+    Watch,
+    Keyboard,
+    Compare,
+    Record,
+    Memory,
+    Wall,
+}
+
+const DEFAULT_ITERATIONS: usize = 10;
+const MAX_ITERATIONS: u64 = 100_000;
+const DEFAULT_SCALE: usize = 8;
+const DEFAULT_SEED: u64 = 2019;
+const DEFAULT_MIN_WIRES: usize = 3;
+const DEFAULT_PASSWORD_LIMIT: usize = 10;
+const MAX_SIF_SIZE: u64 = 10_000;
+
+/// Parses the arguments that follow the program name
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = match args.split_first() {
+        None => return Ok(Command::Help),
+        Some(x) => x,
+    };
+
+    match command.as_str() {
+        "list" => {
+            if let Some(arg) = rest.first() {
+                return Err(format!("Unexpected argument '{}'", arg));
+            }
+
+            Ok(Command::List)
+        }
+
+        "run" => parse_run(rest),
+        "bench" => parse_bench(rest),
+        "image" => parse_image(rest),
+        "sif" => parse_sif(rest),
+        "wires" => parse_wires(rest),
+        "passwords" => parse_passwords(rest),
+        "breakout" => parse_breakout(rest),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        x => Err(format!("Unknown command '{}'", x)),
+    }
+}
+
+fn parse_run(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut all = false;
+    let mut part = None;
+    let mut input = None;
+    let mut format = OutputFormat::Text;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => day = Some(parse_number("--day", iter.next(), 1, 25)?),
+            "--part" => part = Some(parse_number("--part", iter.next(), 1, 2)?),
+            "--input" => match iter.next() {
+                None => return Err("--input requires a path".to_owned()),
+                Some(path) if path == "-" => set_input(&mut input, InputSource::Stdin)?,
+                Some(path) => set_input(&mut input, InputSource::Path(path.clone()))?,
+            },
+
+            "--format" => format = match iter.next().map(|x| x.as_str()) {
+                Some("text") => OutputFormat::Text,
+                Some("json") => OutputFormat::Json,
+                Some(x) => return Err(format!("--format must be 'text' or 'json', found '{}'", x)),
+                None => return Err("--format requires a value".to_owned()),
+            },
+
+            "--input-text" => match iter.next() {
+                None => return Err("--input-text requires the input".to_owned()),
+                Some(text) => set_input(&mut input, InputSource::Inline(text.clone()))?,
+            },
+
+            x => return Err(format!("Unexpected argument '{}'", x)),
+        }
+    }
+
+    let days = select_days(day, all)?;
+    if days == DaySelection::All && input.is_some() {
+        return Err("--input can only be used with a single day".to_owned());
+    }
+
+    Ok(Command::Run(RunOptions { days, part, input, format }))
+}
+
+fn parse_bench(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut all = false;
+    let mut iterations = DEFAULT_ITERATIONS;
+    let mut baseline = bench::BASELINE_PATH.to_owned();
+    let mut save_baseline = false;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--day" => day = Some(parse_number("--day", iter.next(), 1, 25)?),
+            "--iterations" => iterations = parse_large_number("--iterations", iter.next(), 1, MAX_ITERATIONS)? as usize,
+            "--baseline" => baseline = parse_path("--baseline", iter.next())?,
+            "--save-baseline" => save_baseline = true,
+            x => return Err(format!("Unexpected argument '{}'", x)),
+        }
+    }
+
+    let days = select_days(day, all)?;
+    Ok(Command::Bench(BenchOptions { days, iterations, baseline, save_baseline }))
+}
+
+fn parse_image(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut output = None;
+    let mut scale = DEFAULT_SCALE;
+    let mut input = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number("--day", iter.next(), 1, 25)?),
+            "--scale" => scale = parse_number("--scale", iter.next(), 1, 64)? as usize,
+            "--output" => match iter.next() {
+                None => return Err("--output requires a path".to_owned()),
+                Some(path) => output = Some(path.clone()),
+            },
+
+            "--input" => match iter.next() {
+                None => return Err("--input requires a path".to_owned()),
+                Some(path) if path == "-" => set_input(&mut input, InputSource::Stdin)?,
+                Some(path) => set_input(&mut input, InputSource::Path(path.clone()))?,
+            },
+
+            "--input-text" => match iter.next() {
+                None => return Err("--input-text requires the input".to_owned()),
+                Some(text) => set_input(&mut input, InputSource::Inline(text.clone()))?,
+            },
+
+            x => return Err(format!("Unexpected argument '{}'", x)),
+        }
+    }
+
+    let day = day.ok_or_else(|| "--day is required".to_owned())?;
+    let output = output.ok_or_else(|| "--output is required".to_owned())?;
+    let format = ImageFormat::from_path(&output)?;
+    Ok(Command::Image(ImageOptions { day, output, format, scale, input }))
+}
+
+fn parse_sif(args: &[String]) -> Result<Command, String> {
+    let (mode, rest) = match args.split_first() {
+        Some((x, rest)) if x == "encode" || x == "decode" => (x.as_str(), rest),
+        _ => return Err("sif requires 'encode' or 'decode'".to_owned()),
+    };
+
+    let mut input = None;
+    let mut output = None;
+    let mut layers = 1;
+    let mut seed = DEFAULT_SEED;
+    let mut width = None;
+    let mut height = None;
+    let mut scale = DEFAULT_SCALE;
+
+    let mut iter = rest.iter();
+    while let Some(arg) = iter.next() {
+        match (mode, arg.as_str()) {
+            (_, "--input") => input = Some(parse_path("--input", iter.next())?),
+            (_, "--output") => output = Some(parse_path("--output", iter.next())?),
+            ("encode", "--layers") => layers = parse_number("--layers", iter.next(), 1, u8::MAX)? as usize,
+            ("encode", "--seed") => seed = parse_large_number("--seed", iter.next(), 0, u64::MAX)?,
+            ("decode", "--width") => width = Some(parse_large_number("--width", iter.next(), 1, MAX_SIF_SIZE)? as usize),
+            ("decode", "--height") => height = Some(parse_large_number("--height", iter.next(), 1, MAX_SIF_SIZE)? as usize),
+            ("decode", "--scale") => scale = parse_number("--scale", iter.next(), 1, 64)? as usize,
+            (_, x) => return Err(format!("Unexpected argument '{}'", x)),
+        }
+    }
+
+    let input = input.ok_or_else(|| "--input is required".to_owned())?;
+    let output = output.ok_or_else(|| "--output is required".to_owned())?;
+    let conversion = if mode == "encode" {
+        SifConversion::Encode { layers, seed }
+    } else {
+        SifConversion::Decode {
+            width: width.ok_or_else(|| "--width is required".to_owned())?,
+            height: height.ok_or_else(|| "--height is required".to_owned())?,
+            format: ImageFormat::from_path(&output)?,
+            scale,
+        }
+    };
+
+    Ok(Command::Sif(SifOptions { conversion, input, output }))
+}
+
+fn parse_wires(args: &[String]) -> Result<Command, String> {
+    let mut input = None;
+    let mut min_wires = DEFAULT_MIN_WIRES;
+    let mut metric = Metric::Manhattan;
+    let mut svg = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--svg" => svg = Some(parse_path("--svg", iter.next())?),
+            "--min-wires" => min_wires = parse_number("--min-wires", iter.next(), 2, u8::MAX)? as usize,
+            "--metric" => metric = match iter.next().map(|x| x.as_str()) {
+                Some("manhattan") => Metric::Manhattan,
+                Some("walk") => Metric::Walk,
+                Some(x) => return Err(format!("--metric must be 'manhattan' or 'walk', found '{}'", x)),
+                None => return Err("--metric requires a value".to_owned()),
+            },
+
+            "--input" => match iter.next() {
+                None => return Err("--input requires a path".to_owned()),
+                Some(path) if path == "-" => set_input(&mut input, InputSource::Stdin)?,
+                Some(path) => set_input(&mut input, InputSource::Path(path.clone()))?,
+            },
+
+            "--input-text" => match iter.next() {
+                None => return Err("--input-text requires the input".to_owned()),
+                Some(text) => set_input(&mut input, InputSource::Inline(text.clone()))?,
+            },
+
+            x => return Err(format!("Unexpected argument '{}'", x)),
+        }
+    }
+
+    Ok(Command::Wires(WiresOptions { input, min_wires, metric, svg }))
+}
+
+fn parse_passwords(args: &[String]) -> Result<Command, String> {
+    let mut rules = None;
+    let mut range = None;
+    let mut limit = DEFAULT_PASSWORD_LIMIT;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--rules" => match iter.next() {
+                None => return Err("--rules requires the rules".to_owned()),
+                Some(x) => rules = Some(x.clone()),
+            },
+
+            "--range" => match iter.next() {
+                None => return Err("--range requires a range".to_owned()),
+                Some(x) => range = Some(puzzle_04::parse_range(x)?),
+            },
+
+            "--limit" => limit = parse_large_number("--limit", iter.next(), 0, 1_000_000)? as usize,
+            x => return Err(format!("Unexpected argument '{}'", x)),
+        }
+    }
+
+    let rules = rules.ok_or_else(|| "--rules is required".to_owned())?;
+    let (min, max) = range.ok_or_else(|| "--range is required".to_owned())?;
+    Ok(Command::Passwords(PasswordOptions { rules, min, max, limit }))
+}
+
+fn parse_breakout(args: &[String]) -> Result<Command, String> {
+    let mut mode = None;
+    for arg in args {
+        let selected = match arg.as_str() {
+            "--watch" => BreakoutMode::Watch,
+            "--keyboard" => BreakoutMode::Keyboard,
+            "--compare" => BreakoutMode::Compare,
+            "--record" => BreakoutMode::Record,
+            "--memory" => BreakoutMode::Memory,
+            "--wall" => BreakoutMode::Wall,
+            x => return Err(format!("Unexpected argument '{}'", x)),
+        };
+
+        if mode.is_some() {
+            return Err("Only one breakout mode can be given".to_owned());
+        }
+
+        mode = Some(selected);
+    }
+
+    Ok(Command::Breakout(mode.unwrap_or(BreakoutMode::Headless)))
+}
+
+fn select_days(day: Option<u8>, all: bool) -> Result<DaySelection, String> {
+    match (day, all) {
+        (Some(_), true) => Err("--day and --all can't be used together".to_owned()),
+        (None, false) => Err("Either --day or --all is required".to_owned()),
+        (Some(x), false) => Ok(DaySelection::Single(x)),
+        (None, true) => Ok(DaySelection::All),
+    }
+}
+
+fn set_input(input: &mut Option<InputSource>, source: InputSource) -> Result<(), String> {
+    if input.is_some() {
+        return Err("Only one input can be given".to_owned());
+    }
+
+    *input = Some(source);
+    Ok(())
+}
+
+fn parse_number(name: &str, value: Option<&String>, min: u8, max: u8) -> Result<u8, String> {
+    let value = match value {
+        None => return Err(format!("{} requires a value", name)),
+        Some(x) => x,
+    };
+
+    match value.parse::<u8>() {
+        Ok(x) if x >= min && x <= max => Ok(x),
+        _ => Err(format!("{} must be a number from {} to {}, found '{}'", name, min, max, value)),
+    }
+}
+
+fn parse_large_number(name: &str, value: Option<&String>, min: u64, max: u64) -> Result<u64, String> {
+    let value = match value {
+        None => return Err(format!("{} requires a value", name)),
+        Some(x) => x,
+    };
+
+    match value.parse::<u64>() {
+        Ok(x) if x >= min && x <= max => Ok(x),
+        _ => Err(format!("{} must be a number from {} to {}, found '{}'", name, min, max, value)),
+    }
+}
+
+fn parse_path(name: &str, value: Option<&String>) -> Result<String, String> {
+    match value {
+        None => Err(format!("{} requires a path", name)),
+        Some(x) => Ok(x.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        let args: Vec<String> = args.split_whitespace().map(|x| x.to_owned()).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn parses_single_day() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::Single(7),
+            part: Some(2),
+            input: Some(InputSource::Path("inputs/07.txt".to_owned())),
+            format: OutputFormat::Json,
+        });
+
+        assert_eq!(parse("run --day 7 --part 2 --input inputs/07.txt --format json"), Ok(expected));
+    }
+
+    #[test]
+    fn parses_stdin_and_inline_inputs() {
+        let stdin = Command::Run(RunOptions {
+            days: DaySelection::Single(4),
+            part: None,
+            input: Some(InputSource::Stdin),
+            format: OutputFormat::Text,
+        });
+
+        let inline = Command::Run(RunOptions {
+            days: DaySelection::Single(4),
+            part: None,
+            input: Some(InputSource::Inline("100000-200000".to_owned())),
+            format: OutputFormat::Text,
+        });
+
+        assert_eq!(parse("run --day 4 --input -"), Ok(stdin));
+        assert_eq!(parse("run --day 4 --input-text 100000-200000"), Ok(inline));
+        assert!(parse("run --day 4 --input - --input-text 1-2").is_err());
+    }
+
+    #[test]
+    fn parses_all_days() {
+        let expected = Command::Run(RunOptions {
+            days: DaySelection::All,
+            part: None,
+            input: None,
+            format: OutputFormat::Text,
+        });
+
+        assert_eq!(parse("run --all"), Ok(expected));
+        assert_eq!(parse("list"), Ok(Command::List));
+        assert_eq!(parse(""), Ok(Command::Help));
+    }
+
+    #[test]
+    fn parses_bench() {
+        let expected = Command::Bench(BenchOptions {
+            days: DaySelection::Single(16),
+            iterations: 1000,
+            baseline: "baselines/laptop.txt".to_owned(),
+            save_baseline: true,
+        });
+
+        assert_eq!(parse("bench --day 16 --iterations 1000 --baseline baselines/laptop.txt --save-baseline"), Ok(expected));
+        assert_eq!(parse("bench --all"), Ok(Command::Bench(BenchOptions {
+            days: DaySelection::All,
+            iterations: DEFAULT_ITERATIONS,
+            baseline: bench::BASELINE_PATH.to_owned(),
+            save_baseline: false,
+        })));
+
+        assert!(parse("bench --all --iterations 0").is_err());
+        assert!(parse("bench --all --iterations 100001").is_err());
+        assert!(parse("bench --all --baseline").is_err());
+    }
+
+    #[test]
+    fn parses_image() {
+        let expected = Command::Image(ImageOptions {
+            day: 11,
+            output: "hull.svg".to_owned(),
+            format: ImageFormat::Svg,
+            scale: 3,
+            input: Some(InputSource::Stdin),
+        });
+
+        assert_eq!(parse("image --day 11 --output hull.svg --scale 3 --input -"), Ok(expected));
+        assert!(parse("image --day 8").is_err());
+        assert!(parse("image --output day08.png").is_err());
+        assert!(parse("image --day 8 --output day08.bmp").is_err());
+        assert!(parse("image --day 8 --output day08.png --scale 0").is_err());
+    }
+
+    #[test]
+    fn parses_sif() {
+        let encode = Command::Sif(SifOptions {
+            conversion: SifConversion::Encode { layers: 4, seed: DEFAULT_SEED },
+            input: "message.pbm".to_owned(),
+            output: "message.txt".to_owned(),
+        });
+
+        assert_eq!(parse("sif encode --input message.pbm --output message.txt --layers 4"), Ok(encode));
+
+        let decode = Command::Sif(SifOptions {
+            conversion: SifConversion::Decode { width: 25, height: 6, format: ImageFormat::Png, scale: DEFAULT_SCALE },
+            input: "src/inputs/08A.txt".to_owned(),
+            output: "day08.png".to_owned(),
+        });
+
+        assert_eq!(parse("sif decode --input src/inputs/08A.txt --width 25 --height 6 --output day08.png"), Ok(decode));
+        assert!(parse("sif --input a.png --output a.txt").is_err());
+        assert!(parse("sif encode --input a.png --output a.txt --width 3").is_err());
+        assert!(parse("sif decode --input a.txt --width 3 --output a.png").is_err());
+        assert!(parse("sif encode --input a.png --output a.txt --layers 0").is_err());
+    }
+
+    #[test]
+    fn parses_wires() {
+        let expected = Command::Wires(WiresOptions {
+            input: None,
+            min_wires: 4,
+            metric: Metric::Walk,
+            svg: Some("wires.svg".to_owned()),
+        });
+
+        assert_eq!(parse("wires --min-wires 4 --metric walk --svg wires.svg"), Ok(expected));
+
+        let expected = Command::Wires(WiresOptions {
+            input: Some(InputSource::Path("wires.txt".to_owned())),
+            min_wires: DEFAULT_MIN_WIRES,
+            metric: Metric::Manhattan,
+            svg: None,
+        });
+
+        assert_eq!(parse("wires --input wires.txt"), Ok(expected));
+        assert!(parse("wires --svg").is_err());
+        assert!(parse("wires --min-wires 1").is_err());
+        assert!(parse("wires --metric euclidean").is_err());
+    }
+
+    #[test]
+    fn parses_passwords() {
+        let args: Vec<String> = vec!["passwords", "--rules", "run==2 exists; length 6", "--range", "100000-200000", "--limit", "5"]
+            .into_iter()
+            .map(|x| x.to_owned())
+            .collect();
+
+        let expected = Command::Passwords(PasswordOptions {
+            rules: "run==2 exists; length 6".to_owned(),
+            min: 100000,
+            max: 200000,
+            limit: 5,
+        });
+
+        assert_eq!(parse_args(&args), Ok(expected));
+        assert!(parse("passwords --rules length --range 5").is_err());
+        assert!(parse("passwords --range 1-9").is_err());
+    }
+
+    #[test]
+    fn parses_breakout() {
+        assert_eq!(parse("breakout"), Ok(Command::Breakout(BreakoutMode::Headless)));
+        assert_eq!(parse("breakout --watch"), Ok(Command::Breakout(BreakoutMode::Watch)));
+        assert_eq!(parse("breakout --keyboard"), Ok(Command::Breakout(BreakoutMode::Keyboard)));
+        assert_eq!(parse("breakout --compare"), Ok(Command::Breakout(BreakoutMode::Compare)));
+        assert_eq!(parse("breakout --record"), Ok(Command::Breakout(BreakoutMode::Record)));
+        assert_eq!(parse("breakout --memory"), Ok(Command::Breakout(BreakoutMode::Memory)));
+        assert_eq!(parse("breakout --wall"), Ok(Command::Breakout(BreakoutMode::Wall)));
+        assert!(parse("breakout --watch --record").is_err());
+        assert!(parse("breakout --day 13").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse("run").is_err());
+        assert!(parse("run --day 26").is_err());
+        assert!(parse("run --day 3 --part 3").is_err());
+        assert!(parse("run --day 3 --all").is_err());
+        assert!(parse("run --all --input x.txt").is_err());
+        assert!(parse("run --all --input-text 1-2").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("run --day 3 --format yaml").is_err());
+        assert!(parse("walk").is_err());
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::HashMap;
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+
+/// How many earlier positions with the same 3 bytes are checked for the longest match
+const MAX_CHAIN: usize = 32;
+
+const END_OF_BLOCK: u16 = 256;
+const MAX_CODE_LENGTH: usize = 15;
+
+/// Order the code length code lengths are stored in by dynamic blocks
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+/// Shortest length for each length code from 257, and how many extra bits follow the code
+const LENGTH_BASES: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83,
+                                 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA_BITS: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5,
+                                     5, 0];
+
+/// Shortest distance for each distance code, and how many extra bits follow the code
+const DISTANCE_BASES: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769,
+                                   1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DISTANCE_EXTRA_BITS: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11,
+                                       11, 12, 12, 13, 13];
+
+/// Writes bits starting from the least significant bit of each byte, as deflate streams do
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    bit_count: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            bytes: Vec::new(),
+            current: 0,
+            bit_count: 0,
+        }
+    }
+
+    fn write(&mut self, value: u16, size: u8) {
+        self.current |= (value as u32) << self.bit_count ;
+        self.bit_count += size;
+        while self.bit_count >= 8 {
+            self.bytes.push((self.current & 0xFF) as u8);
+            self.current >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    /// Huffman codes are stored most significant bit first, unlike every other value
+    fn write_code(&mut self, code: u16, size: u8) {
+        let mut reversed = 0;
+        for bit in 0..size {
+            reversed = (reversed << 1) | ((code >> bit) & 1);
+        }
+
+        self.write(reversed, size);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push((self.current & 0xFF) as u8);
+        }
+
+        self.bytes
+    }
+}
+
+/// Reads bits starting from the least significant bit of each byte
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    bit: u8,
+}
+
+/// A canonical Huffman code, stored as how many codes have each length and the symbols in code
+/// order, which is all that's needed to decode it a bit at a time
+struct Huffman {
+    counts: [u16; MAX_CODE_LENGTH + 1],
+    symbols: Vec<u16>,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0, bit: 0 }
+    }
+
+    fn read(&mut self, size: u8) -> Result<u16, String> {
+        let mut value = 0;
+        for index in 0..size {
+            let byte = match self.bytes.get(self.position) {
+                None => return Err("Compressed data ended unexpectedly".to_owned()),
+                Some(x) => *x,
+            };
+
+            value |= (((byte >> self.bit) & 1) as u16) << index ;
+            self.bit += 1;
+            if self.bit == 8 {
+                self.bit = 0;
+                self.position += 1;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Skips to the start of the next byte, as stored blocks begin on byte boundaries
+    fn align(&mut self) {
+        if self.bit > 0 {
+            self.bit = 0;
+            self.position += 1;
+        }
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        if self.position + count > self.bytes.len() {
+            return Err("Compressed data ended unexpectedly".to_owned());
+        }
+
+        let result = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(result)
+    }
+}
+
+impl Huffman {
+    /// Builds the code from the length of each symbol's code, where 0 means the symbol is unused
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0; MAX_CODE_LENGTH + 1];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+
+        counts[0] = 0;
+        let mut symbols = Vec::new();
+        for length in 1..=MAX_CODE_LENGTH {
+            for (symbol, x) in lengths.iter().enumerate() {
+                if *x as usize == length {
+                    symbols.push(symbol as u16);
+                }
+            }
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, String> {
+        let mut code = 0;
+        let mut first = 0;
+        let mut index = 0;
+        for length in 1..=MAX_CODE_LENGTH {
+            code |= reader.read(1)? as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err("Invalid Huffman code in compressed data".to_owned())
+    }
+}
+
+/// Compresses the data into a raw deflate stream, as a single block using the fixed Huffman codes
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    writer.write(1, 1); // Final block
+    writer.write(1, 2); // Fixed Huffman codes
+
+    // Most recent position of each 3 byte sequence, and the position before it with the same bytes
+    let mut heads: HashMap<&[u8], usize> = HashMap::new();
+    let mut previous = vec![usize::MAX; data.len()];
+
+    let mut position = 0;
+    while position < data.len() {
+        let (length, distance) = longest_match(data, position, &heads, &previous);
+        if length >= MIN_MATCH {
+            write_length(&mut writer, length);
+            write_distance(&mut writer, distance);
+            for x in position..(position + length) {
+                remember(data, x, &mut heads, &mut previous);
+            }
+
+            position += length;
+        } else {
+            write_literal(&mut writer, data[position] as u16);
+            remember(data, position, &mut heads, &mut previous);
+            position += 1;
+        }
+    }
+
+    write_literal(&mut writer, END_OF_BLOCK);
+    writer.finish()
+}
+
+fn remember<'a>(data: &'a [u8], position: usize, heads: &mut HashMap<&'a [u8], usize>, previous: &mut [usize]) {
+    if position + MIN_MATCH <= data.len() {
+        if let Some(head) = heads.insert(&data[position..position + MIN_MATCH], position) {
+            previous[position] = head;
+        }
+    }
+}
+
+/// Finds the longest earlier copy of the bytes at `position`, returning its length and distance
+fn longest_match(data: &[u8], position: usize, heads: &HashMap<&[u8], usize>, previous: &[usize]) -> (usize, usize) {
+    if position + MIN_MATCH > data.len() {
+        return (0, 0);
+    }
+
+    let mut best = (0, 0);
+    let mut candidate = heads.get(&data[position..position + MIN_MATCH]).cloned().unwrap_or(usize::MAX);
+    let max_length = MAX_MATCH.min(data.len() - position);
+    for _ in 0..MAX_CHAIN {
+        if candidate == usize::MAX || position - candidate > WINDOW_SIZE {
+            break;
+        }
+
+        let mut length = 0;
+        while length < max_length && data[candidate + length] == data[position + length] {
+            length += 1;
+        }
+
+        if length > best.0 {
+            best = (length, position - candidate);
+            if length == max_length {
+                break;
+            }
+        }
+
+        candidate = previous[candidate];
+    }
+
+    best
+}
+
+/// Writes a literal byte or length code (0 to 287) with its fixed Huffman code
+fn write_literal(writer: &mut BitWriter, value: u16) {
+    match value {
+        0..=143 => writer.write_code(0x30 + value, 8),
+        144..=255 => writer.write_code(0x190 + value - 144, 9),
+        256..=279 => writer.write_code(value - 256, 7),
+        _ => writer.write_code(0xC0 + value - 280, 8),
+    }
+}
+
+fn write_length(writer: &mut BitWriter, length: usize) {
+    let index = LENGTH_BASES.iter().rposition(|x| *x as usize <= length).unwrap();
+    write_literal(writer, 257 + index as u16);
+    writer.write(length as u16 - LENGTH_BASES[index], LENGTH_EXTRA_BITS[index]);
+}
+
+fn write_distance(writer: &mut BitWriter, distance: usize) {
+    let index = DISTANCE_BASES.iter().rposition(|x| *x as usize <= distance).unwrap();
+    writer.write_code(index as u16, 5);
+    writer.write(distance as u16 - DISTANCE_BASES[index], DISTANCE_EXTRA_BITS[index]);
+}
+
+/// Decompresses a raw deflate stream, with stored, fixed and dynamic Huffman blocks
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut reader = BitReader::new(data);
+    let mut output = Vec::new();
+    loop {
+        let is_final = reader.read(1)? == 1;
+        match reader.read(2)? {
+            0 => {
+                reader.align();
+                let header = reader.read_bytes(4)?;
+                let length = u16::from_le_bytes([header[0], header[1]]);
+                let complement = u16::from_le_bytes([header[2], header[3]]);
+                if length != !complement {
+                    return Err("Stored block length doesn't match its complement".to_owned());
+                }
+
+                output.extend_from_slice(reader.read_bytes(length as usize)?);
+            }
+
+            1 => {
+                let (literals, distances) = fixed_codes();
+                inflate_block(&mut reader, &literals, &distances, &mut output)?;
+            }
+
+            2 => {
+                let (literals, distances) = read_dynamic_codes(&mut reader)?;
+                inflate_block(&mut reader, &literals, &distances, &mut output)?;
+            }
+
+            x => return Err(format!("Invalid block type {}", x)),
+        }
+
+        if is_final {
+            return Ok(output);
+        }
+    }
+}
+
+fn fixed_codes() -> (Huffman, Huffman) {
+    let mut lengths = [0; 288];
+    for (symbol, length) in lengths.iter_mut().enumerate() {
+        *length = match symbol {
+            0..=143 => 8,
+            144..=255 => 9,
+            256..=279 => 7,
+            _ => 8,
+        };
+    }
+
+    (Huffman::new(&lengths), Huffman::new(&[5; 30]))
+}
+
+fn read_dynamic_codes(reader: &mut BitReader) -> Result<(Huffman, Huffman), String> {
+    let literal_count = reader.read(5)? as usize + 257;
+    let distance_count = reader.read(5)? as usize + 1;
+    let code_length_count = reader.read(4)? as usize + 4;
+
+    let mut code_length_lengths = [0; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_length_count) {
+        code_length_lengths[*index] = reader.read(3)? as u8;
+    }
+
+    let code_lengths = Huffman::new(&code_length_lengths);
+    let mut lengths: Vec<u8> = Vec::new();
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_lengths.decode(reader)? {
+            x @ 0..=15 => (x as u8, 1),
+            16 => match lengths.last() {
+                None => return Err("Code lengths start with a repeat".to_owned()),
+                Some(x) => (*x, 3 + reader.read(2)?),
+            },
+            17 => (0, 3 + reader.read(3)?),
+            _ => (0, 11 + reader.read(7)?),
+        };
+
+        for _ in 0..repeat {
+            lengths.push(value);
+        }
+    }
+
+    if lengths.len() > literal_count + distance_count {
+        return Err("Code lengths run past the end of the table".to_owned());
+    }
+
+    let (literals, distances) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literals), Huffman::new(distances)))
+}
+
+fn inflate_block(reader: &mut BitReader, literals: &Huffman, distances: &Huffman, output: &mut Vec<u8>) -> Result<(), String> {
+    loop {
+        let symbol = literals.decode(reader)?;
+        if symbol < END_OF_BLOCK {
+            output.push(symbol as u8);
+            continue;
+        }
+
+        if symbol == END_OF_BLOCK {
+            return Ok(());
+        }
+
+        let index = (symbol - 257) as usize;
+        if index >= LENGTH_BASES.len() {
+            return Err(format!("Invalid length code {}", symbol));
+        }
+
+        let length = (LENGTH_BASES[index] + reader.read(LENGTH_EXTRA_BITS[index])?) as usize;
+        let index = distances.decode(reader)? as usize;
+        if index >= DISTANCE_BASES.len() {
+            return Err(format!("Invalid distance code {}", index));
+        }
+
+        let distance = (DISTANCE_BASES[index] + reader.read(DISTANCE_EXTRA_BITS[index])?) as usize;
+        if distance > output.len() {
+            return Err(format!("Copy reaches {} bytes back, but only {} have been written", distance, output.len()));
+        }
+
+        // Copies can overlap the bytes they produce, so they're made a byte at a time
+        let start = output.len() - distance;
+        for x in 0..length {
+            output.push(output[start + x]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_input_is_a_lone_end_of_block() {
+        assert_eq!(compress(&[]), vec![0x03, 0x00]);
+    }
+
+    #[test]
+    fn repeats_are_stored_as_copies() {
+        let data = vec![7u8; 10000];
+        assert!(compress(&data).len() < 100);
+    }
+
+    #[test]
+    fn decompresses_what_it_compresses() {
+        let data: Vec<u8> = b"abracadabra ".iter().cycle().take(500).cloned().chain(0..=255).collect();
+        assert_eq!(decompress(&compress(&data)), Ok(data));
+        assert_eq!(decompress(&compress(&[])), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn decompresses_stored_and_dynamic_blocks() {
+        let stored = [0x01, 0x06, 0x00, 0xf9, 0xff, b's', b't', b'o', b'r', b'e', b'd'];
+        assert_eq!(decompress(&stored), Ok(b"stored".to_vec()));
+
+        // Written by zlib with Huffman coding only, which picks a dynamic block
+        let dynamic = [0x05, 0xc1, 0xb1, 0x09, 0x00, 0x31, 0x10, 0x04, 0xb1, 0x56, 0xa6, 0xb5, 0xe5, 0x59, 0xb8,
+            0xc4, 0xbe, 0xc0, 0xd3, 0x3f, 0x2f, 0x39, 0xe5, 0x8b, 0xbc, 0xc8, 0x5e, 0x9c, 0x72, 0x22, 0x4e, 0xf9,
+            0x22, 0x2f, 0xb2, 0x17, 0xa7, 0x9c, 0x88, 0x53, 0xbe, 0xc8, 0x8b, 0xec, 0xc5, 0x29, 0x27, 0xd2, 0xb6,
+            0x6d, 0xab, 0x6a, 0x92, 0xdd, 0xbd, 0x3f];
+        let expected = "the cat sat on the mat ".repeat(3) + "eeeeeeeetttttaaaooon";
+        assert_eq!(decompress(&dynamic), Ok(expected.into_bytes()));
+        assert!(decompress(&dynamic[..20]).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use console::Term;
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
+
+/// Red, green and blue components of a color
+pub type Rgb = (u8, u8, u8);
+
+/// How a single buffer value is shown when rendered
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Swatch {
+    pub symbol: char,
+    pub color: Rgb,
+}
+
+/// Maps buffer values to how they are drawn.  Values without an explicit entry are drawn with
+/// the fallback swatch.
+#[derive(Clone, Debug)]
+pub struct Palette {
+    entries: HashMap<i128, Swatch>,
+    fallback: Swatch,
+}
+
+/// A named coordinate that doesn't represent a pixel (e.g. breakout's score at `(-1, 0)`)
+#[derive(Clone, Debug)]
+pub struct Channel {
+    pub name: String,
+    pub x: i128,
+    pub y: i128,
+    pub value: Option<i128>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Update {
+    Pixel { x: usize, y: usize, value: i128 },
+    Channel { name: String, value: i128 },
+}
+
+/// Framebuffer for Intcode programs that draw by outputting `(x, y, value)` triples.  The
+/// buffer grows as pixels are written, with every unwritten pixel holding 0.
+pub struct Display {
+    pub palette: Palette,
+    width: usize,
+    height: usize,
+    pixels: Vec<i128>,
+    channels: Vec<Channel>,
+}
+
+/// Draws a display onto a terminal, with one line per channel above the pixel buffer
+pub struct TerminalScreen {
+    term: Term,
+}
+
+impl Palette {
+    pub fn new(fallback: Swatch) -> Self {
+        Palette {
+            entries: HashMap::new(),
+            fallback,
+        }
+    }
+
+    pub fn set(&mut self, value: i128, symbol: char, color: Rgb) {
+        self.entries.insert(value, Swatch { symbol, color });
+    }
+
+    pub fn get(&self, value: i128) -> &Swatch {
+        match self.entries.get(&value) {
+            None => &self.fallback,
+            Some(swatch) => swatch,
+        }
+    }
+}
+
+impl Display {
+    pub fn new(palette: Palette) -> Self {
+        Display {
+            palette,
+            width: 0,
+            height: 0,
+            pixels: Vec::new(),
+            channels: Vec::new(),
+        }
+    }
+
+    /// Treats writes to the specified coordinate as a named value instead of a pixel
+    pub fn add_channel(&mut self, name: &str, x: i128, y: i128) {
+        self.channels.push(Channel {
+            name: name.to_owned(),
+            x,
+            y,
+            value: None,
+        });
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Every pixel, row by row
+    pub fn pixels(&self) -> &[i128] {
+        &self.pixels
+    }
+
+    pub fn channels(&self) -> &[Channel] {
+        &self.channels
+    }
+
+    pub fn channel(&self, name: &str) -> Option<i128> {
+        self.channels.iter()
+            .find(|x| x.name == name)
+            .and_then(|x| x.value)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> i128 {
+        if x >= self.width || y >= self.height {
+            0
+        } else {
+            self.pixels[y * self.width + x]
+        }
+    }
+
+    /// Number of pixels currently holding the specified value
+    pub fn count(&self, value: i128) -> usize {
+        self.pixels.iter().filter(|x| **x == value).count()
+    }
+
+    /// Drains every complete triple from the buffer, leaving any partial triple in place
+    pub fn consume(&mut self, output: &mut VecDeque<i128>) -> Vec<Update> {
+        let mut updates = Vec::new();
+        while output.len() >= 3 {
+            let values = output.drain(0..3).collect::<Vec<i128>>();
+            updates.push(self.write(values[0], values[1], values[2]));
+        }
+
+        updates
+    }
+
+    pub fn write(&mut self, x: i128, y: i128, value: i128) -> Update {
+        if let Some(channel) = self.channels.iter_mut().find(|c| c.x == x && c.y == y) {
+            channel.value = Some(value);
+            return Update::Channel { name: channel.name.clone(), value };
+        }
+
+        if x < 0 || y < 0 {
+            panic!("Pixel ({}, {}) is outside of the display", x, y);
+        }
+
+        let (x, y) = (x as usize, y as usize);
+        self.resize_if_needed(x, y);
+        self.pixels[y * self.width + x] = value;
+
+        Update::Pixel { x, y, value }
+    }
+
+    /// Renders the pixel buffer as one line of palette symbols per row
+    pub fn render_text(&self) -> String {
+        let mut result = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.push(self.palette.get(self.get(x, y)).symbol);
+            }
+
+            result.push('\n');
+        }
+
+        result
+    }
+
+    /// Renders the pixel buffer as a binary PPM image, with each pixel drawn as a
+    /// `scale` x `scale` square
+    pub fn render_ppm(&self, scale: usize) -> Vec<u8> {
+        let mut result = format!("P6\n{} {}\n255\n", self.width * scale, self.height * scale).into_bytes();
+        for y in 0..(self.height * scale) {
+            for x in 0..(self.width * scale) {
+                let (red, green, blue) = self.palette.get(self.get(x / scale, y / scale)).color;
+                result.push(red);
+                result.push(green);
+                result.push(blue);
+            }
+        }
+
+        result
+    }
+
+    fn resize_if_needed(&mut self, x: usize, y: usize) {
+        if x < self.width && y < self.height {
+            return;
+        }
+
+        let new_width = self.width.max(x + 1);
+        let new_height = self.height.max(y + 1);
+        let mut pixels = vec![0; new_width * new_height];
+        for row in 0..self.height {
+            let old_start = row * self.width;
+            let new_start = row * new_width;
+            pixels[new_start..(new_start + self.width)]
+                .copy_from_slice(&self.pixels[old_start..(old_start + self.width)]);
+        }
+
+        self.width = new_width;
+        self.height = new_height;
+        self.pixels = pixels;
+    }
+}
+
+impl TerminalScreen {
+    pub fn new(term: Term) -> Self {
+        TerminalScreen { term }
+    }
+
+    pub fn clear(&mut self) {
+        self.term.clear_screen().unwrap();
+    }
+
+    /// Redraws every channel and pixel
+    pub fn draw_all(&mut self, display: &Display) {
+        for channel in display.channels() {
+            if let Some(value) = channel.value {
+                self.draw(display, &Update::Channel { name: channel.name.clone(), value });
+            }
+        }
+
+        for y in 0..display.height() {
+            for x in 0..display.width() {
+                self.draw(display, &Update::Pixel { x, y, value: display.get(x, y) });
+            }
+        }
+    }
+
+    pub fn draw(&mut self, display: &Display, update: &Update) {
+        match update {
+            Update::Channel { name, value } => {
+                let row = display.channels().iter().position(|x| x.name == *name).unwrap();
+                self.term.move_cursor_to(0, row + 1).unwrap();
+                self.term.write_all(format!("{}: {}", name, value).as_bytes()).unwrap();
+            }
+
+            Update::Pixel { x, y, value } => {
+                let symbol = display.palette.get(*value).symbol;
+                self.term.move_cursor_to(*x, y + self.top_row(display)).unwrap();
+                self.term.write_all(symbol.to_string().as_bytes()).unwrap();
+            }
+        }
+    }
+
+    /// Moves the cursor to the first line after the display and writes the message
+    pub fn write_below(&mut self, display: &Display, message: &str) {
+        self.term.move_cursor_to(0, display.height() + self.top_row(display) + 1).unwrap();
+        self.term.write_line(message).unwrap();
+    }
+
+    fn top_row(&self, display: &Display) -> usize {
+        display.channels().len() + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_display() -> Display {
+        let mut palette = Palette::new(Swatch { symbol: '.', color: (0, 0, 0) });
+        palette.set(1, '#', (255, 255, 255));
+
+        let mut display = Display::new(palette);
+        display.add_channel("Score", -1, 0);
+        display
+    }
+
+    #[test]
+    fn consume_leaves_partial_triples() {
+        let mut display = test_display();
+        let mut output: VecDeque<i128> = vec![2, 1, 1, -1, 0, 30, 0].into_iter().collect();
+        let updates = display.consume(&mut output);
+
+        assert_eq!(updates, vec![
+            Update::Pixel { x: 2, y: 1, value: 1 },
+            Update::Channel { name: "Score".to_owned(), value: 30 },
+        ]);
+
+        assert_eq!(output, vec![0]);
+        assert_eq!(display.channel("Score"), Some(30));
+        assert_eq!((display.width(), display.height()), (3, 2));
+    }
+
+    #[test]
+    fn growing_keeps_existing_pixels() {
+        let mut display = test_display();
+        display.write(1, 0, 1);
+        display.write(0, 2, 1);
+        display.write(3, 1, 1);
+
+        assert_eq!(display.render_text(), ".#..\n...#\n#...\n");
+        assert_eq!(display.count(1), 3);
+    }
+
+    #[test]
+    fn ppm_is_scaled() {
+        let mut display = test_display();
+        display.write(1, 0, 1);
+
+        let image = display.render_ppm(2);
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&image[..header.len()], &header[..]);
+        assert_eq!(image.len(), header.len() + 4 * 2 * 3);
+        assert_eq!(&image[header.len()..(header.len() + 12)], &[0, 0, 0, 0, 0, 0, 255, 255, 255, 255, 255, 255]);
+    }
+}
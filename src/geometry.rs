@@ -0,0 +1,477 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{Add, Sub};
+
+/// Integer types that can be used as point coordinates
+pub trait Coordinate: Copy + Eq + Ord + Hash + Debug + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn to_i128(self) -> i128;
+}
+
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    };
+}
+
+coordinate!(i32, i64, i128, u32, usize);
+
+/// A position on a 2D grid in screen coordinates, so y grows going south
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Direction { North, East, South, West }
+
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub enum Turn { Left, Right }
+
+/// The smallest rectangle holding a set of points, with both corners inclusive
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct BoundingBox<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+/// A fixed size grid storing a value for every cell, row by row
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// A grid that only stores the cells that have been set, for maps with no fixed size
+#[derive(Debug, Clone)]
+pub struct SparseGrid<C, T> {
+    cells: HashMap<Point<C>, T>,
+}
+
+impl<T: Coordinate> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+
+    pub fn origin() -> Self {
+        Point { x: T::ZERO, y: T::ZERO }
+    }
+
+    /// The adjacent point in the specified direction
+    pub fn step(&self, direction: Direction) -> Self {
+        match self.checked_step(direction) {
+            None => panic!("Can't move {:?} from {:?}", direction, self),
+            Some(x) => x,
+        }
+    }
+
+    /// The point `distance` steps away in the specified direction
+    pub fn step_by(&self, direction: Direction, distance: T) -> Self {
+        let moved = match direction {
+            Direction::North => self.y.checked_sub(distance).map(|y| Point { x: self.x, y }),
+            Direction::South => self.y.checked_add(distance).map(|y| Point { x: self.x, y }),
+            Direction::East => self.x.checked_add(distance).map(|x| Point { x, y: self.y }),
+            Direction::West => self.x.checked_sub(distance).map(|x| Point { x, y: self.y }),
+        };
+
+        match moved {
+            None => panic!("Can't move {:?} {:?} steps from {:?}", direction, distance, self),
+            Some(x) => x,
+        }
+    }
+
+    /// The adjacent point in the specified direction, or `None` if it can't be represented (e.g.
+    /// north of `y = 0` with unsigned coordinates)
+    pub fn checked_step(&self, direction: Direction) -> Option<Self> {
+        match direction {
+            Direction::North => self.y.checked_sub(T::ONE).map(|y| Point { x: self.x, y }),
+            Direction::South => self.y.checked_add(T::ONE).map(|y| Point { x: self.x, y }),
+            Direction::East => self.x.checked_add(T::ONE).map(|x| Point { x, y: self.y }),
+            Direction::West => self.x.checked_sub(T::ONE).map(|x| Point { x, y: self.y }),
+        }
+    }
+
+    /// Orthogonally adjacent points, in the same order as `Direction::ALL`
+    pub fn neighbors(&self) -> Vec<Self> {
+        Direction::ALL.iter()
+            .filter_map(|direction| self.checked_step(*direction))
+            .collect()
+    }
+
+    pub fn manhattan_distance(&self, other: &Point<T>) -> T {
+        difference(self.x, other.x) + difference(self.y, other.y)
+    }
+}
+
+fn difference<T: Coordinate>(first: T, second: T) -> T {
+    if first > second { first - second } else { second - first }
+}
+
+impl Direction {
+    /// Every direction, going clockwise from north
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn turn(&self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.turn_left().turn_left()
+    }
+
+    /// Which way to turn to face the other direction, if it's a single turn away
+    pub fn turn_towards(&self, other: Direction) -> Option<Turn> {
+        if self.turn_left() == other {
+            Some(Turn::Left)
+        } else if self.turn_right() == other {
+            Some(Turn::Right)
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Coordinate> BoundingBox<T> {
+    pub fn new(point: Point<T>) -> Self {
+        BoundingBox { min: point, max: point }
+    }
+
+    pub fn from_points<'a, I>(points: I) -> Option<Self>
+        where I: IntoIterator<Item = &'a Point<T>>, T: 'a {
+        let mut result: Option<BoundingBox<T>> = None;
+        for point in points {
+            match result.as_mut() {
+                None => result = Some(BoundingBox::new(*point)),
+                Some(bounds) => bounds.include(point),
+            }
+        }
+
+        result
+    }
+
+    /// Grows the box so it holds the point
+    pub fn include(&mut self, point: &Point<T>) {
+        self.min.x = self.min.x.min(point.x);
+        self.min.y = self.min.y.min(point.y);
+        self.max.x = self.max.x.max(point.x);
+        self.max.y = self.max.y.max(point.y);
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x.to_i128() - self.min.x.to_i128() + 1) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y.to_i128() - self.min.y.to_i128() + 1) as usize
+    }
+
+    /// Every point in the box, row by row
+    pub fn points(&self) -> Vec<Point<T>> {
+        let mut result = Vec::new();
+        let mut y = self.min.y;
+        loop {
+            let mut x = self.min.x;
+            loop {
+                result.push(Point { x, y });
+                if x == self.max.x {
+                    break;
+                }
+
+                x = x + T::ONE;
+            }
+
+            if y == self.max.y {
+                break;
+            }
+
+            y = y + T::ONE;
+        }
+
+        result
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid with one cell per character, where every line must be the same length
+    pub fn parse<F>(text: &str, mut convert: F) -> Self where F: FnMut(char) -> T {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for line in text.lines().filter(|x| !x.trim().is_empty()) {
+            let line = line.trim_end();
+            let line_width = line.chars().count();
+            match width {
+                None => width = Some(line_width),
+                Some(x) if x != line_width => {
+                    panic!("Row {} is {} wide but earlier rows are {} wide", height, line_width, x)
+                }
+                Some(_) => (),
+            }
+
+            cells.extend(line.chars().map(&mut convert));
+            height += 1;
+        }
+
+        Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: &Point<usize>) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: &Point<usize>) -> Option<&T> {
+        if self.contains(point) {
+            Some(&self.cells[point.y * self.width + point.x])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, point: &Point<usize>, value: T) {
+        if !self.contains(point) {
+            panic!("{:?} is outside of the {}x{} grid", point, self.width, self.height);
+        }
+
+        self.cells[point.y * self.width + point.x] = value;
+    }
+
+    /// Adjacent points that are inside the grid
+    pub fn neighbors(&self, point: &Point<usize>) -> Vec<Point<usize>> {
+        point.neighbors().into_iter().filter(|x| self.contains(x)).collect()
+    }
+
+    /// Every point in the grid, row by row
+    pub fn points(&self) -> Vec<Point<usize>> {
+        let mut result = Vec::with_capacity(self.cells.len());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                result.push(Point { x, y });
+            }
+        }
+
+        result
+    }
+
+    /// Every cell along with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point<usize>, &T)> {
+        let width = self.width;
+        self.cells.iter().enumerate().map(move |(index, value)| (Point { x: index % width, y: index / width }, value))
+    }
+
+    /// Renders one line of characters per row
+    pub fn render<F>(&self, symbol: F) -> String where F: Fn(&T) -> char {
+        let mut rows = Vec::new();
+        for row in self.cells.chunks(self.width.max(1)) {
+            rows.push(row.iter().map(&symbol).collect::<String>());
+        }
+
+        rows.join("\n")
+    }
+}
+
+impl<C: Coordinate, T> SparseGrid<C, T> {
+    pub fn new() -> Self {
+        SparseGrid { cells: HashMap::new() }
+    }
+
+    pub fn insert(&mut self, point: Point<C>, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    pub fn get(&self, point: &Point<C>) -> Option<&T> {
+        self.cells.get(point)
+    }
+
+    pub fn contains(&self, point: &Point<C>) -> bool {
+        self.cells.contains_key(point)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Point<C>, &T)> {
+        self.cells.iter()
+    }
+
+    /// The smallest box holding every set cell
+    pub fn bounds(&self) -> Option<BoundingBox<C>> {
+        BoundingBox::from_points(self.cells.keys())
+    }
+
+    /// Renders every row of the bounding box, with unset cells passed to `symbol` as `None`
+    pub fn render<F>(&self, symbol: F) -> String where F: Fn(Option<&T>) -> char {
+        let bounds = match self.bounds() {
+            None => return String::new(),
+            Some(x) => x,
+        };
+
+        let mut rows = Vec::new();
+        for row in bounds.points().chunks(bounds.width()) {
+            rows.push(row.iter().map(|point| symbol(self.get(point))).collect::<String>());
+        }
+
+        rows.join("\n")
+    }
+}
+
+impl<C: Coordinate, T: Clone> SparseGrid<C, T> {
+    /// Copies the cells into a dense grid covering their bounds, with the top left corner moved
+    /// to the origin and `fill` in every cell that wasn't set
+    pub fn to_grid(&self, fill: T) -> Grid<T> {
+        let bounds = match self.bounds() {
+            None => return Grid::new(0, 0, fill),
+            Some(x) => x,
+        };
+
+        let mut grid = Grid::new(bounds.width(), bounds.height(), fill);
+        for (point, value) in self.iter() {
+            let x = (point.x.to_i128() - bounds.min.x.to_i128()) as usize;
+            let y = (point.y.to_i128() - bounds.min.y.to_i128()) as usize;
+            grid.set(&Point { x, y }, value.clone());
+        }
+
+        grid
+    }
+}
+
+impl<C: Coordinate, T> Default for SparseGrid<C, T> {
+    fn default() -> Self {
+        SparseGrid::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_and_turns() {
+        let point = Point::new(2, 5);
+        assert_eq!(point.step(Direction::North), Point::new(2, 4));
+        assert_eq!(point.step(Direction::East), Point::new(3, 5));
+        assert_eq!(point.step_by(Direction::South, 1000), Point::new(2, 1005));
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+        assert_eq!(Direction::West.turn(Turn::Right), Direction::North);
+        assert_eq!(Direction::East.opposite(), Direction::West);
+        assert_eq!(Direction::West.turn_towards(Direction::North), Some(Turn::Right));
+        assert_eq!(Direction::West.turn_towards(Direction::East), None);
+    }
+
+    #[test]
+    fn unsigned_points_stop_at_zero() {
+        let corner: Point<u32> = Point::origin();
+        assert_eq!(corner.checked_step(Direction::North), None);
+        assert_eq!(corner.neighbors(), vec![Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(Point::new(1u32, 7).manhattan_distance(&Point::new(4, 2)), 8);
+    }
+
+    #[test]
+    fn bounding_boxes() {
+        let points = vec![Point::new(-2, 3), Point::new(4, -1), Point::new(0, 0)];
+        let bounds = BoundingBox::from_points(&points).unwrap();
+
+        assert_eq!(bounds, BoundingBox { min: Point::new(-2, -1), max: Point::new(4, 3) });
+        assert_eq!((bounds.width(), bounds.height()), (7, 5));
+        assert!(bounds.contains(&Point::new(4, 3)));
+        assert!(!bounds.contains(&Point::new(5, 3)));
+        assert_eq!(bounds.points().len(), 35);
+    }
+
+    #[test]
+    fn dense_grid() {
+        let mut grid = Grid::parse("#.\n..\n.#\n", |ch| ch == '#');
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.get(&Point::new(1, 2)), Some(&true));
+        assert_eq!(grid.get(&Point::new(2, 0)), None);
+        assert_eq!(grid.neighbors(&Point::new(0, 0)), vec![Point::new(1, 0), Point::new(0, 1)]);
+
+        grid.set(&Point::new(0, 1), true);
+        assert_eq!(grid.render(|x| if *x { '#' } else { '.' }), "#.\n#.\n.#");
+    }
+
+    #[test]
+    fn sparse_grid_renders_its_bounds() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Point::new(-1, -1), 'a');
+        grid.insert(Point::new(1, 0), 'b');
+
+        assert_eq!(grid.render(|x| *x.unwrap_or(&' ')), "a  \n  b");
+        assert_eq!(grid.to_grid('.').render(|x| *x), "a..\n..b");
+    }
+}
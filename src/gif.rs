@@ -0,0 +1,362 @@
+use crate::display::Rgb;
+use std::collections::HashMap;
+
+const MAX_CODE_SIZE: u8 = 12;
+const MAX_SUB_BLOCK_SIZE: usize = 255;
+
+/// Builds a looping animated GIF from frames of color table indices.  Only the region that
+/// changed since the previous frame is stored, which keeps long animations small.
+pub struct Animation {
+    width: usize,
+    height: usize,
+    colors: Vec<Rgb>,
+    delay: u16,
+    previous: Option<Vec<u8>>,
+    data: Vec<u8>,
+}
+
+struct BitWriter {
+    bytes: Vec<u8>,
+    current: u32,
+    bit_count: u8,
+}
+
+impl Animation {
+    /// `delay` is the time each frame is shown for, in hundredths of a second.  At most 256
+    /// colors are supported.
+    pub fn new(width: usize, height: usize, colors: Vec<Rgb>, delay: u16) -> Self {
+        if colors.is_empty() || colors.len() > 256 {
+            panic!("GIF images need between 1 and 256 colors, {} given", colors.len());
+        }
+
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            panic!("GIF images can be at most {}x{}, {}x{} given", u16::MAX, u16::MAX, width, height);
+        }
+
+        Animation {
+            width,
+            height,
+            colors,
+            delay,
+            previous: None,
+            data: Vec::new(),
+        }
+    }
+
+    /// Adds a frame of `width * height` color indices, row by row
+    pub fn add_frame(&mut self, pixels: Vec<u8>) {
+        if pixels.len() != self.width * self.height {
+            panic!("Expected {} pixels in the frame, found {}", self.width * self.height, pixels.len());
+        }
+
+        let (left, top, width, height) = match &self.previous {
+            None => (0, 0, self.width, self.height),
+            // When nothing changed a single pixel is stored, as the frame still needs to be shown
+            Some(previous) => changed_area(previous, &pixels, self.width, self.height).unwrap_or((0, 0, 1, 1)),
+        };
+
+        let mut region = Vec::with_capacity(width * height);
+        for y in top..(top + height) {
+            let start = y * self.width + left;
+            region.extend_from_slice(&pixels[start..(start + width)]);
+        }
+
+        // Graphic control extension with the frame's delay
+        self.data.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        push_u16(&mut self.data, self.delay);
+        self.data.extend_from_slice(&[0x00, 0x00]);
+
+        // Image descriptor without a local color table
+        self.data.push(0x2C);
+        push_u16(&mut self.data, left as u16);
+        push_u16(&mut self.data, top as u16);
+        push_u16(&mut self.data, width as u16);
+        push_u16(&mut self.data, height as u16);
+        self.data.push(0x00);
+
+        let min_code_size = self.color_table_bits().max(2);
+        self.data.push(min_code_size);
+        let compressed = compress(&region, min_code_size);
+        for block in compressed.chunks(MAX_SUB_BLOCK_SIZE) {
+            self.data.push(block.len() as u8);
+            self.data.extend_from_slice(block);
+        }
+
+        self.data.push(0x00);
+        self.previous = Some(pixels);
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let table_bits = self.color_table_bits();
+        let mut result = Vec::new();
+        result.extend_from_slice(b"GIF89a");
+
+        // Logical screen descriptor with a global color table
+        push_u16(&mut result, self.width as u16);
+        push_u16(&mut result, self.height as u16);
+        result.push(0x80 | 0x70 | (table_bits - 1));
+        result.extend_from_slice(&[0x00, 0x00]);
+
+        for index in 0..(1 << table_bits) {
+            let (red, green, blue) = self.colors.get(index).cloned().unwrap_or((0, 0, 0));
+            result.extend_from_slice(&[red, green, blue]);
+        }
+
+        // Netscape extension so the animation loops forever
+        result.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        result.extend_from_slice(b"NETSCAPE2.0");
+        result.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        result.extend_from_slice(&self.data);
+        result.push(0x3B);
+        result
+    }
+
+    /// Number of bits needed to index the color table, which must hold at least 2 colors
+    fn color_table_bits(&self) -> u8 {
+        let mut bits = 1;
+        while (1 << bits) < self.colors.len() {
+            bits += 1;
+        }
+
+        bits
+    }
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        BitWriter {
+            bytes: Vec::new(),
+            current: 0,
+            bit_count: 0,
+        }
+    }
+
+    fn write(&mut self, code: u16, size: u8) {
+        self.current |= (code as u32) << self.bit_count ;
+        self.bit_count += size;
+        while self.bit_count >= 8 {
+            self.bytes.push((self.current & 0xFF) as u8);
+            self.current >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push((self.current & 0xFF) as u8);
+        }
+
+        self.bytes
+    }
+}
+
+/// Returns the smallest `(left, top, width, height)` rectangle holding every changed pixel
+fn changed_area(previous: &[u8], current: &[u8], width: usize, height: usize) -> Option<(usize, usize, usize, usize)> {
+    let mut min_x = width;
+    let mut min_y = height;
+    let mut max_x = 0;
+    let mut max_y = 0;
+    let mut has_changes = false;
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            if previous[index] != current[index] {
+                has_changes = true;
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+            }
+        }
+    }
+
+    if has_changes {
+        Some((min_x, min_y, max_x - min_x + 1, max_y - min_y + 1))
+    } else {
+        None
+    }
+}
+
+/// GIF flavored LZW compression with variable length codes
+fn compress(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear_code = 1u16 << min_code_size;
+    let end_code = clear_code + 1;
+    let mut writer = BitWriter::new();
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end_code + 1;
+    let mut code_size = min_code_size + 1;
+    let mut current: Option<u16> = None;
+
+    writer.write(clear_code, code_size);
+    for index in indices {
+        let prefix = match current {
+            None => {
+                current = Some(*index as u16);
+                continue;
+            }
+
+            Some(x) => x,
+        };
+
+        if let Some(code) = table.get(&(prefix, *index)) {
+            current = Some(*code);
+            continue;
+        }
+
+        // Decoders add a table entry one code behind us, so they widen codes one code later
+        if next_code > (1 << code_size) && code_size < MAX_CODE_SIZE {
+            code_size += 1;
+        }
+
+        writer.write(prefix, code_size);
+        table.insert((prefix, *index), next_code);
+        next_code += 1;
+        current = Some(*index as u16);
+
+        if next_code == (1 << MAX_CODE_SIZE) - 1 {
+            writer.write(clear_code, code_size);
+            table.clear();
+            next_code = end_code + 1;
+            code_size = min_code_size + 1;
+        }
+    }
+
+    if let Some(code) = current {
+        if next_code > (1 << code_size) && code_size < MAX_CODE_SIZE {
+            code_size += 1;
+        }
+
+        writer.write(code, code_size);
+        next_code += 1;
+    }
+
+    if next_code > (1 << code_size) && code_size < MAX_CODE_SIZE {
+        code_size += 1;
+    }
+
+    writer.write(end_code, code_size);
+    writer.finish()
+}
+
+fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference GIF LZW decoder, following the variable code size rules decoders use
+    fn decompress(data: &[u8], min_code_size: u8) -> Vec<u8> {
+        let clear_code = 1usize << min_code_size;
+        let end_code = clear_code + 1;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut code_size = min_code_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut result = Vec::new();
+        let mut bit_position = 0;
+
+        let reset = |table: &mut Vec<Vec<u8>>| {
+            table.clear();
+            for x in 0..(clear_code + 2) {
+                table.push(vec![x as u8]);
+            }
+        };
+
+        reset(&mut table);
+        loop {
+            let mut code = 0;
+            for bit in 0..code_size {
+                let byte = data[(bit_position + bit as usize) / 8];
+                let value = (byte >> ((bit_position + bit as usize) % 8)) & 1;
+                code |= (value as usize) << bit;
+            }
+
+            bit_position += code_size as usize;
+
+            if code == clear_code {
+                reset(&mut table);
+                code_size = min_code_size + 1;
+                previous = None;
+                continue;
+            }
+
+            if code == end_code {
+                break;
+            }
+
+            let entry = match (&previous, code < table.len()) {
+                (_, true) => table[code].clone(),
+                (Some(previous), false) => {
+                    let mut entry = previous.clone();
+                    entry.push(previous[0]);
+                    entry
+                }
+
+                (None, false) => panic!("Code {} is not in the table", code),
+            };
+
+            if let Some(previous) = previous {
+                let mut new_entry = previous.clone();
+                new_entry.push(entry[0]);
+                table.push(new_entry);
+                if table.len() == (1 << code_size) && code_size < MAX_CODE_SIZE {
+                    code_size += 1;
+                }
+            }
+
+            result.extend_from_slice(&entry);
+            previous = Some(entry);
+        }
+
+        result
+    }
+
+    #[test]
+    fn compression_round_trips() {
+        let mut pseudo_random = 17u32;
+        let mut noisy = Vec::new();
+        for _ in 0..20000 {
+            pseudo_random = pseudo_random.wrapping_mul(1103515245).wrapping_add(12345);
+            noisy.push(((pseudo_random >> 16) % 4) as u8);
+        }
+
+        let samples = vec![
+            vec![0],
+            vec![1, 1, 1, 1, 1, 1, 1, 1, 1, 1],
+            (0..5000).map(|x| (x % 3) as u8).collect(),
+            noisy,
+        ];
+
+        for sample in samples {
+            assert_eq!(decompress(&compress(&sample, 2), 2), sample);
+        }
+    }
+
+    #[test]
+    fn unchanged_frames_are_stored_as_a_single_pixel() {
+        let mut animation = Animation::new(4, 4, vec![(0, 0, 0), (255, 255, 255)], 10);
+        animation.add_frame(vec![0; 16]);
+        let first_frame_size = animation.data.len();
+        animation.add_frame(vec![0; 16]);
+
+        let second_frame = &animation.data[first_frame_size..];
+        assert_eq!(second_frame[8], 0x2C);
+        assert_eq!(&second_frame[13..17], &[1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn encoded_file_has_header_and_trailer() {
+        let mut animation = Animation::new(2, 1, vec![(0, 0, 0), (255, 0, 0), (0, 255, 0)], 5);
+        animation.add_frame(vec![0, 2]);
+        let bytes = animation.encode();
+
+        assert_eq!(&bytes[0..6], b"GIF89a");
+        assert_eq!(&bytes[6..10], &[2, 0, 1, 0]);
+        assert_eq!(bytes[10] & 0x07, 1); // 4 entry color table
+        assert_eq!(&bytes[13..25], &[0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 0]);
+        assert_eq!(*bytes.last().unwrap(), 0x3B);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::display::{Palette, Rgb};
+use crate::geometry::{Grid, Point};
+use crate::png;
+use std::path::Path;
+
+pub const BLACK: Rgb = (0, 0, 0);
+pub const WHITE: Rgb = (255, 255, 255);
+/// The most pixels a decoded image may have, so a corrupt header can't ask for more memory than
+/// any puzzle picture needs
+pub const MAX_PIXELS: usize = 1 << 26;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ImageFormat { Ppm, Png, Svg }
+
+/// A picture with one color per pixel, which can be written out as PPM, PNG or SVG with each
+/// pixel drawn as a `scale` x `scale` square
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl ImageFormat {
+    /// Picks the format from a file's extension
+    pub fn from_path(path: &str) -> Result<Self, String> {
+        let extension = Path::new(path).extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("ppm") => Ok(ImageFormat::Ppm),
+            Some("png") => Ok(ImageFormat::Png),
+            Some("svg") => Ok(ImageFormat::Svg),
+            _ => Err(format!("Can't tell the image format of '{}', use a .ppm, .png or .svg extension", path)),
+        }
+    }
+}
+
+impl Image {
+    pub fn from_grid<T, F>(grid: &Grid<T>, color: F) -> Self where F: Fn(&T) -> Rgb {
+        Image {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(_, value)| color(value)).collect(),
+        }
+    }
+
+    /// Set cells are drawn white on black
+    pub fn from_bitmap(grid: &Grid<bool>) -> Self {
+        Image::from_grid(grid, |x| if *x { WHITE } else { BLACK })
+    }
+
+    pub fn from_palette(grid: &Grid<i128>, palette: &Palette) -> Self {
+        Image::from_grid(grid, |x| palette.get(*x).color)
+    }
+
+    /// Reads a PNG or PBM (plain `P1` or raw `P4`) image, telling them apart by their header
+    pub fn decode(data: &[u8]) -> Result<Self, String> {
+        if data.starts_with(b"P1") || data.starts_with(b"P4") {
+            return decode_pbm(data);
+        }
+
+        let decoded = png::decode(data)?;
+        Ok(Image { width: decoded.width, height: decoded.height, pixels: decoded.pixels })
+    }
+
+    /// Pixels at least half as bright as white are set
+    pub fn to_bitmap(&self) -> Grid<bool> {
+        let mut result = Grid::new(self.width, self.height, false);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (red, green, blue) = self.get(x, y);
+                let brightness = (red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000;
+                result.set(&Point::new(x, y), brightness >= 128);
+            }
+        }
+
+        result
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn encode(&self, format: ImageFormat, scale: usize) -> Vec<u8> {
+        match format {
+            ImageFormat::Ppm => self.to_ppm(scale),
+            ImageFormat::Png => self.to_png(scale),
+            ImageFormat::Svg => self.to_svg(scale).into_bytes(),
+        }
+    }
+
+    /// Binary PPM
+    pub fn to_ppm(&self, scale: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.scaled(scale);
+        let mut result = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        for (red, green, blue) in pixels {
+            result.extend_from_slice(&[red, green, blue]);
+        }
+
+        result
+    }
+
+    pub fn to_png(&self, scale: usize) -> Vec<u8> {
+        let (width, height, pixels) = self.scaled(scale);
+        png::encode(width, height, &pixels)
+    }
+
+    /// SVG with a rectangle for each horizontal run of same colored pixels
+    pub fn to_svg(&self, scale: usize) -> String {
+        check_scale(scale);
+        let mut result = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" shape-rendering=\"crispEdges\">\n",
+                                 self.width * scale, self.height * scale);
+
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let color = self.get(x, y);
+                let mut run = 1;
+                while x + run < self.width && self.get(x + run, y) == color {
+                    run += 1;
+                }
+
+                result.push_str(&format!("  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                                         x * scale, y * scale, run * scale, scale, hex_color(color)));
+                x += run;
+            }
+        }
+
+        result.push_str("</svg>\n");
+        result
+    }
+
+    fn scaled(&self, scale: usize) -> (usize, usize, Vec<Rgb>) {
+        check_scale(scale);
+        let mut pixels = Vec::with_capacity(self.pixels.len() * scale * scale);
+        for y in 0..(self.height * scale) {
+            for x in 0..(self.width * scale) {
+                pixels.push(self.get(x / scale, y / scale));
+            }
+        }
+
+        (self.width * scale, self.height * scale, pixels)
+    }
+}
+
+/// PBM images store a 1 for each black pixel
+fn decode_pbm(data: &[u8]) -> Result<Image, String> {
+    let mut position = 2;
+    let width = read_pbm_number(data, &mut position)?;
+    let height = read_pbm_number(data, &mut position)?;
+    let count = pixel_count(width, height)?;
+    let mut pixels = Vec::with_capacity(count);
+    if data[1] == b'1' {
+        for byte in &data[position..] {
+            match byte {
+                b'0' => pixels.push(WHITE),
+                b'1' => pixels.push(BLACK),
+                x if x.is_ascii_whitespace() => (),
+                x => return Err(format!("Unexpected '{}' in PBM pixels", *x as char)),
+            }
+        }
+
+        pixels.truncate(count);
+    } else {
+        // A single whitespace byte separates the header from packed rows of bits
+        let start = position + 1;
+        let stride = width.div_ceil(8);
+        if data.len() < start + stride * height {
+            return Err(format!("Expected {} bytes of PBM pixels, found {}", stride * height,
+                               data.len().saturating_sub(start)));
+        }
+
+        for y in 0..height {
+            for x in 0..width {
+                let byte = data[start + y * stride + x / 8];
+                pixels.push(if (byte >> (7 - x % 8)) & 1 == 1 { BLACK } else { WHITE });
+            }
+        }
+    }
+
+    if pixels.len() != count {
+        return Err(format!("Expected {} PBM pixels, found {}", count, pixels.len()));
+    }
+
+    Ok(Image { width, height, pixels })
+}
+
+fn read_pbm_number(data: &[u8], position: &mut usize) -> Result<usize, String> {
+    // Whitespace and comments can appear between any header values
+    loop {
+        match data.get(*position) {
+            Some(b'#') => {
+                while *position < data.len() && data[*position] != b'\n' {
+                    *position += 1;
+                }
+            }
+
+            Some(x) if x.is_ascii_whitespace() => *position += 1,
+            _ => break,
+        }
+    }
+
+    let start = *position;
+    while *position < data.len() && data[*position].is_ascii_digit() {
+        *position += 1;
+    }
+
+    match std::str::from_utf8(&data[start..*position]).unwrap().parse() {
+        Ok(x) => Ok(x),
+        Err(_) => Err(format!("Expected a number at byte {} of the PBM header", start)),
+    }
+}
+
+/// The number of pixels in a `width` x `height` image, if it isn't more than `MAX_PIXELS`
+pub fn pixel_count(width: usize, height: usize) -> Result<usize, String> {
+    match width.checked_mul(height) {
+        Some(x) if x <= MAX_PIXELS => Ok(x),
+        _ => Err(format!("A {}x{} image has more than the {} pixels allowed", width, height, MAX_PIXELS)),
+    }
+}
+
+pub fn hex_color((red, green, blue): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+fn check_scale(scale: usize) {
+    if scale == 0 {
+        panic!("Images must be drawn at a scale of at least 1");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        Image::from_bitmap(&Grid::parse("#.\n.#", |ch| ch == '#'))
+    }
+
+    #[test]
+    fn writes_scaled_ppm() {
+        let ppm = checkerboard().to_ppm(2);
+        let header = b"P6\n4 4\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 4 * 4 * 3);
+        assert_eq!(&ppm[header.len()..header.len() + 12], &[255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn writes_svg_runs() {
+        let image = Image::from_bitmap(&Grid::parse("##.", |ch| ch == '#'));
+        let svg = image.to_svg(3);
+        assert!(svg.contains("width=\"9\" height=\"3\""));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"6\" height=\"3\" fill=\"#ffffff\"/>"));
+        assert!(svg.contains("<rect x=\"6\" y=\"0\" width=\"3\" height=\"3\" fill=\"#000000\"/>"));
+    }
+
+    #[test]
+    fn picks_format_from_extension() {
+        assert_eq!(ImageFormat::from_path("out/day08.PNG"), Ok(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_path("day17.svg"), Ok(ImageFormat::Svg));
+        assert!(ImageFormat::from_path("day17").is_err());
+        assert_eq!(&checkerboard().encode(ImageFormat::Png, 1)[1..4], b"PNG");
+    }
+
+    #[test]
+    fn decodes_pbm_and_png() {
+        let plain = Image::decode(b"P1\n# a comment\n2 2\n0 1\n1 0\n").unwrap();
+        assert_eq!(plain, checkerboard());
+
+        let raw = Image::decode(&[b'P', b'4', b'\n', b'2', b' ', b'2', b'\n', 0b0100_0000, 0b1000_0000]).unwrap();
+        assert_eq!(raw, checkerboard());
+        assert!(Image::decode(b"P1\n2 2\n0 1 1").is_err());
+
+        let png = Image::decode(&checkerboard().to_png(1)).unwrap();
+        assert_eq!(png.to_bitmap().render(|x| if *x { '#' } else { '.' }), "#.\n.#");
+    }
+
+    #[test]
+    fn rejects_oversized_pbm() {
+        assert_eq!(Image::decode(b"P1\n3000000000 3\n0"),
+                   Err(format!("A 3000000000x3 image has more than the {} pixels allowed", MAX_PIXELS)));
+        assert!(Image::decode(b"P1\n4294967296 4294967296\n").is_err());
+        assert!(Image::decode(b"P4\n4294967296 4294967296\n").is_err());
+    }
+}
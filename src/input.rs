@@ -0,0 +1,39 @@
+use std::fs;
+use std::io::{self, Read};
+
+/// Where a puzzle's input comes from
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum InputSource {
+    Path(String),
+    Stdin,
+    Inline(String),
+}
+
+impl InputSource {
+    /// The input stored with the repository for the specified day
+    pub fn for_day(day: u8) -> Self {
+        InputSource::Path(format!("src/inputs/{:02}A.txt", day))
+    }
+
+    pub fn load(&self) -> Result<String, String> {
+        match self {
+            InputSource::Path(path) => fs::read_to_string(path)
+                .map_err(|error| format!("Could not read input from {}: {}", path, error)),
+
+            InputSource::Stdin => {
+                let mut content = String::new();
+                match io::stdin().read_to_string(&mut content) {
+                    Ok(_) => Ok(content),
+                    Err(error) => Err(format!("Could not read input from stdin: {}", error)),
+                }
+            }
+
+            InputSource::Inline(content) => Ok(content.clone()),
+        }
+    }
+}
+
+/// Reads the input stored with the repository for the specified day
+pub fn read_day(day: u8) -> String {
+    InputSource::for_day(day).load().unwrap()
+}
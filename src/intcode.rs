@@ -32,13 +32,7 @@ impl Machine {
         let mut content = String::new();
         file.read_to_string(&mut content).unwrap();
 
-        let mut memory = Vec::new();
-        for code in content.trim().split(",") {
-            let code_as_int = code.parse::<i128>().unwrap();
-            memory.push(code_as_int);
-        }
-
-        Machine::new_from_memory(memory)
+        Machine::new_from_memory(parse_program(&content))
     }
     
     pub fn run_program(&mut self) -> MachineState {
@@ -48,40 +42,40 @@ impl Machine {
             match &instruction.op_code {
                 1 => {
                     // Add
-                    let left_param_val = self.memory[self.instruction_pointer as usize + 1];
-                    let right_param_val = self.memory[self.instruction_pointer as usize + 2];
-                    let mut result_param_val = self.memory[self.instruction_pointer as usize + 3];
+                    let left_param_val = self.memory[self.instruction_pointer + 1];
+                    let right_param_val = self.memory[self.instruction_pointer + 2];
+                    let mut result_param_val = self.memory[self.instruction_pointer + 3];
 
                     let left = self.read_param_value(left_param_val, &instruction.param1_mode);
                     let right = self.read_param_value(right_param_val, &instruction.param2_mode);
                     let output = left + right;
 
                     if instruction.param3_mode == ParameterMode::Relative {
-                        result_param_val = self.relative_base + result_param_val;
+                        result_param_val += self.relative_base;
                     }
 
                     self.resize_if_needed(result_param_val);
                     self.memory[result_param_val as usize] = output;
-                    self.instruction_pointer = self.instruction_pointer + 4;
+                    self.instruction_pointer += 4;
                 }
 
                 2 => {
                     // Multiply
-                    let left_param_val = self.memory[self.instruction_pointer as usize + 1];
-                    let right_param_val = self.memory[self.instruction_pointer as usize + 2];
-                    let mut result_param_val = self.memory[self.instruction_pointer as usize + 3];
+                    let left_param_val = self.memory[self.instruction_pointer + 1];
+                    let right_param_val = self.memory[self.instruction_pointer + 2];
+                    let mut result_param_val = self.memory[self.instruction_pointer + 3];
 
                     let left = self.read_param_value(left_param_val, &instruction.param1_mode);
                     let right = self.read_param_value(right_param_val, &instruction.param2_mode);
                     let output = left * right;
 
                     if instruction.param3_mode == ParameterMode::Relative {
-                        result_param_val = self.relative_base + result_param_val;
+                        result_param_val += self.relative_base;
                     }
 
                     self.resize_if_needed(result_param_val);
                     self.memory[result_param_val as usize] = output;
-                    self.instruction_pointer = self.instruction_pointer + 4;
+                    self.instruction_pointer += 4;
                 }
 
                 3 => {
@@ -95,19 +89,19 @@ impl Machine {
                     //println!("Original Address {}, base: {}", output_address, self.relative_base);
 
                     if instruction.param1_mode == ParameterMode::Relative {
-                        output_address = self.relative_base + output_address;
+                        output_address += self.relative_base;
                     }
 
                     self.resize_if_needed(output_address);
                     self.memory[output_address as usize] = input;
-                    self.instruction_pointer = self.instruction_pointer + 2;
+                    self.instruction_pointer += 2;
                 }
 
                 4 => {
                     let param_val = self.memory[self.instruction_pointer  + 1];
                     let output = self.read_param_value(param_val, &instruction.param1_mode);
                     self.output_buffer.push_back(output);
-                    self.instruction_pointer = self.instruction_pointer + 2;
+                    self.instruction_pointer += 2;
                 }
 
                 5 => {
@@ -149,12 +143,12 @@ impl Machine {
                     let mut store_pos = param3_val;
 
                     if instruction.param3_mode == ParameterMode::Relative {
-                        store_pos = self.relative_base + store_pos;
+                        store_pos += self.relative_base;
                     }
 
                     self.resize_if_needed(store_pos);
                     self.memory[store_pos as usize] = if left < right { 1 } else { 0 };
-                    self.instruction_pointer = self.instruction_pointer + 4;
+                    self.instruction_pointer += 4;
                 }
 
                 8 => {
@@ -168,12 +162,12 @@ impl Machine {
                     let mut store_pos = param3_val;
 
                     if instruction.param3_mode == ParameterMode::Relative {
-                        store_pos = self.relative_base + store_pos;
+                        store_pos += self.relative_base;
                     }
 
                     self.resize_if_needed(store_pos);
                     self.memory[store_pos as usize] = if left == right { 1 } else { 0 };
-                    self.instruction_pointer = self.instruction_pointer + 4;
+                    self.instruction_pointer += 4;
                 }
 
                 9 => {
@@ -183,8 +177,8 @@ impl Machine {
 
                     //println!("Relative base change by {} + {} = {}", self.relative_base, change, self.relative_base + change);
 
-                    self.relative_base = self.relative_base + change;
-                    self.instruction_pointer = self.instruction_pointer + 2;
+                    self.relative_base += change;
+                    self.instruction_pointer += 2;
                 }
 
                 99 => {
@@ -220,6 +214,17 @@ impl Machine {
     }
 }
 
+/// Parses a comma separated Intcode program
+pub fn parse_program(content: &str) -> Vec<i128> {
+    let mut memory = Vec::new();
+    for code in content.trim().split(",") {
+        let code_as_int = code.parse::<i128>().unwrap();
+        memory.push(code_as_int);
+    }
+
+    memory
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 enum ParameterMode { Position, Immediate, Relative }
 
@@ -252,4 +257,389 @@ fn get_param_mode(value: i128) -> ParameterMode {
         2 => ParameterMode::Relative,
         x => panic!("Parameter mode {} is not valid", x),
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELATIVE_BASE: i128 = 50;
+    const FIRST_SLOT: usize = 60;
+    const SECOND_SLOT: usize = 61;
+    const RESULT_SLOT: usize = 62;
+    const MODES: [i128; 3] = [0, 1, 2];
+    const WRITE_MODES: [i128; 2] = [0, 2];
+
+    fn run(program: Vec<i128>, inputs: &[i128]) -> (Machine, MachineState) {
+        let mut machine = Machine::new_from_memory(program);
+        machine.input_buffer.extend(inputs.iter());
+        let state = machine.run_program();
+
+        (machine, state)
+    }
+
+    fn outputs(program: Vec<i128>, inputs: &[i128]) -> Vec<i128> {
+        let (machine, state) = run(program, inputs);
+        assert_eq!(state, MachineState::Halted);
+
+        machine.output_buffer.into_iter().collect()
+    }
+
+    /// Returns the raw parameter that resolves to `value` under `mode`, storing the value at
+    /// `slot` when the mode reads from memory.
+    fn read_param(mode: i128, value: i128, slot: usize, memory: &mut [i128]) -> i128 {
+        match mode {
+            0 => {
+                memory[slot] = value;
+                slot as i128
+            }
+            1 => value,
+            2 => {
+                memory[slot] = value;
+                slot as i128 - RELATIVE_BASE
+            }
+            x => panic!("Unsupported mode {}", x),
+        }
+    }
+
+    /// Returns the raw parameter that addresses `slot` under the (position or relative) `mode`
+    fn write_param(mode: i128, slot: usize) -> i128 {
+        match mode {
+            0 => slot as i128,
+            2 => slot as i128 - RELATIVE_BASE,
+            x => panic!("Unsupported write mode {}", x),
+        }
+    }
+
+    /// Builds a program that sets the relative base and then executes a single three parameter
+    /// instruction with the requested modes before halting.
+    fn binary_op_program(op_code: i128, modes: (i128, i128, i128), left: i128, right: i128) -> Vec<i128> {
+        let mut memory = vec![0; RESULT_SLOT + 1];
+        let (mode1, mode2, mode3) = modes;
+        let instruction = mode3 * 10000 + mode2 * 1000 + mode1 * 100 + op_code;
+
+        memory[0] = 109;
+        memory[1] = RELATIVE_BASE;
+        memory[2] = instruction;
+        memory[3] = read_param(mode1, left, FIRST_SLOT, &mut memory);
+        memory[4] = read_param(mode2, right, SECOND_SLOT, &mut memory);
+        memory[5] = write_param(mode3, RESULT_SLOT);
+        memory[6] = 99;
+
+        memory
+    }
+
+    fn run_binary_op(op_code: i128, left: i128, right: i128, expected: i128) {
+        for mode1 in &MODES {
+            for mode2 in &MODES {
+                for mode3 in &WRITE_MODES {
+                    let modes = (*mode1, *mode2, *mode3);
+                    let program = binary_op_program(op_code, modes, left, right);
+                    let (machine, state) = run(program, &[]);
+
+                    assert_eq!(state, MachineState::Halted, "op {} modes {:?}", op_code, modes);
+                    assert_eq!(machine.memory[RESULT_SLOT], expected, "op {} modes {:?}", op_code, modes);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn add_supports_all_parameter_modes() {
+        run_binary_op(1, 7, 35, 42);
+        run_binary_op(1, -7, 3, -4);
+    }
+
+    #[test]
+    fn multiply_supports_all_parameter_modes() {
+        run_binary_op(2, 6, 7, 42);
+        run_binary_op(2, -6, 7, -42);
+    }
+
+    #[test]
+    fn less_than_supports_all_parameter_modes() {
+        run_binary_op(7, 3, 4, 1);
+        run_binary_op(7, 4, 4, 0);
+        run_binary_op(7, 5, 4, 0);
+    }
+
+    #[test]
+    fn equals_supports_all_parameter_modes() {
+        run_binary_op(8, 4, 4, 1);
+        run_binary_op(8, 3, 4, 0);
+    }
+
+    #[test]
+    fn input_supports_position_and_relative_modes() {
+        for mode in &WRITE_MODES {
+            let mut program = vec![0; RESULT_SLOT + 1];
+            program[0] = 109;
+            program[1] = RELATIVE_BASE;
+            program[2] = mode * 100 + 3;
+            program[3] = write_param(*mode, RESULT_SLOT);
+            program[4] = 99;
+
+            let (machine, state) = run(program, &[1234]);
+            assert_eq!(state, MachineState::Halted, "mode {}", mode);
+            assert_eq!(machine.memory[RESULT_SLOT], 1234, "mode {}", mode);
+        }
+    }
+
+    #[test]
+    fn output_supports_all_parameter_modes() {
+        for mode in &MODES {
+            let mut program = vec![0; RESULT_SLOT + 1];
+            program[0] = 109;
+            program[1] = RELATIVE_BASE;
+            program[2] = mode * 100 + 4;
+            program[3] = read_param(*mode, -77, FIRST_SLOT, &mut program);
+            program[4] = 99;
+
+            assert_eq!(outputs(program, &[]), vec![-77], "mode {}", mode);
+        }
+    }
+
+    /// Runs a jump instruction where the jump target outputs 1 and falling through outputs 0
+    fn run_jump(op_code: i128, check_value: i128) -> Vec<Vec<i128>> {
+        let mut results = Vec::new();
+        for mode1 in &MODES {
+            for mode2 in &MODES {
+                let mut program = vec![0; RESULT_SLOT + 1];
+                program[0] = 109;
+                program[1] = RELATIVE_BASE;
+                program[2] = mode2 * 1000 + mode1 * 100 + op_code;
+                program[3] = read_param(*mode1, check_value, FIRST_SLOT, &mut program);
+                program[4] = read_param(*mode2, 9, SECOND_SLOT, &mut program);
+                program[5] = 104; // fall through
+                program[6] = 0;
+                program[7] = 99;
+                program[8] = 99;
+                program[9] = 104; // jump target
+                program[10] = 1;
+                program[11] = 99;
+
+                results.push(outputs(program, &[]));
+            }
+        }
+
+        results
+    }
+
+    #[test]
+    fn jump_if_true_supports_all_parameter_modes() {
+        assert!(run_jump(5, 3).iter().all(|x| *x == vec![1]));
+        assert!(run_jump(5, 0).iter().all(|x| *x == vec![0]));
+    }
+
+    #[test]
+    fn jump_if_false_supports_all_parameter_modes() {
+        assert!(run_jump(6, 0).iter().all(|x| *x == vec![1]));
+        assert!(run_jump(6, -3).iter().all(|x| *x == vec![0]));
+    }
+
+    #[test]
+    fn adjust_relative_base_supports_all_parameter_modes() {
+        for mode in &MODES {
+            // Sets the base to 50, then adjusts it by 5 and outputs base relative address 0
+            let mut program = vec![0; RESULT_SLOT + 1];
+            program[0] = 109;
+            program[1] = RELATIVE_BASE;
+            program[2] = mode * 100 + 9;
+            program[3] = read_param(*mode, 5, FIRST_SLOT, &mut program);
+            program[4] = 204;
+            program[5] = 0;
+            program[6] = 99;
+            program[RELATIVE_BASE as usize + 5] = 555;
+
+            assert_eq!(outputs(program, &[]), vec![555], "mode {}", mode);
+        }
+    }
+
+    #[test]
+    fn relative_base_accumulates_and_can_move_backwards() {
+        let program = vec![109, 10, 109, 5, 109, -3, 204, 0, 99, 0, 0, 0, 88];
+        assert_eq!(outputs(program, &[]), vec![88]);
+    }
+
+    #[test]
+    fn relative_base_persists_between_runs() {
+        let program = vec![109, 10, 203, 0, 204, 0, 99, 0, 0, 0, 0];
+        let (mut machine, state) = run(program, &[]);
+        assert_eq!(state, MachineState::WaitingForInput);
+
+        machine.input_buffer.push_back(7);
+        assert_eq!(machine.run_program(), MachineState::Halted);
+        assert_eq!(machine.memory[10], 7);
+        assert_eq!(machine.output_buffer, vec![7]);
+    }
+
+    #[test]
+    fn waits_for_input_and_resumes() {
+        let (mut machine, state) = run(vec![3, 0, 4, 0, 3, 0, 4, 0, 99], &[11]);
+        assert_eq!(state, MachineState::WaitingForInput);
+        assert_eq!(machine.output_buffer, vec![11]);
+
+        machine.input_buffer.push_back(22);
+        assert_eq!(machine.run_program(), MachineState::Halted);
+        assert_eq!(machine.output_buffer, vec![11, 22]);
+    }
+
+    #[test]
+    fn reading_beyond_image_returns_zero() {
+        assert_eq!(outputs(vec![4, 1000, 99], &[]), vec![0]);
+        assert_eq!(outputs(vec![109, 500, 204, 500, 99], &[]), vec![0]);
+    }
+
+    #[test]
+    fn writing_beyond_image_grows_memory() {
+        let (machine, _) = run(vec![1101, 20, 22, 100, 4, 100, 99], &[]);
+        assert_eq!(machine.memory.len(), 101);
+        assert_eq!(machine.memory[100], 42);
+        assert_eq!(machine.output_buffer, vec![42]);
+
+        let (machine, _) = run(vec![109, 200, 21101, 1, 2, 50, 3, 300, 99], &[9]);
+        assert_eq!(machine.memory[250], 3);
+        assert_eq!(machine.memory[300], 9);
+        assert!(machine.memory.len() >= 301);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown opcode")]
+    fn unknown_opcode_panics() {
+        run(vec![42, 0, 0, 0, 99], &[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Parameter mode 3 is not valid")]
+    fn unknown_parameter_mode_panics() {
+        run(vec![304, 0, 99], &[]);
+    }
+
+    #[test]
+    fn day_2_examples() {
+        let examples = vec![
+            (vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50], vec![3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50]),
+            (vec![1, 0, 0, 0, 99], vec![2, 0, 0, 0, 99]),
+            (vec![2, 3, 0, 3, 99], vec![2, 3, 0, 6, 99]),
+            (vec![2, 4, 4, 5, 99, 0], vec![2, 4, 4, 5, 99, 9801]),
+            (vec![1, 1, 1, 4, 99, 5, 6, 0, 99], vec![30, 1, 1, 4, 2, 5, 6, 0, 99]),
+        ];
+
+        for (program, expected) in examples {
+            let (machine, state) = run(program, &[]);
+            assert_eq!(state, MachineState::Halted);
+            assert_eq!(machine.memory, expected);
+        }
+    }
+
+    #[test]
+    fn day_5_io_and_immediate_mode_examples() {
+        assert_eq!(outputs(vec![3, 0, 4, 0, 99], &[-31]), vec![-31]);
+
+        let (machine, _) = run(vec![1002, 4, 3, 4, 33], &[]);
+        assert_eq!(machine.memory, vec![1002, 4, 3, 4, 99]);
+
+        let (machine, _) = run(vec![1101, 100, -1, 4, 0], &[]);
+        assert_eq!(machine.memory, vec![1101, 100, -1, 4, 99]);
+    }
+
+    #[test]
+    fn day_5_comparison_examples() {
+        let equal_position = vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8];
+        let less_position = vec![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8];
+        let equal_immediate = vec![3, 3, 1108, -1, 8, 3, 4, 3, 99];
+        let less_immediate = vec![3, 3, 1107, -1, 8, 3, 4, 3, 99];
+
+        for input in &[7, 8, 9] {
+            let is_equal = if *input == 8 { 1 } else { 0 };
+            let is_less = if *input < 8 { 1 } else { 0 };
+
+            assert_eq!(outputs(equal_position.clone(), &[*input]), vec![is_equal]);
+            assert_eq!(outputs(less_position.clone(), &[*input]), vec![is_less]);
+            assert_eq!(outputs(equal_immediate.clone(), &[*input]), vec![is_equal]);
+            assert_eq!(outputs(less_immediate.clone(), &[*input]), vec![is_less]);
+        }
+    }
+
+    #[test]
+    fn day_5_jump_examples() {
+        let position = vec![3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9];
+        let immediate = vec![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1];
+
+        assert_eq!(outputs(position.clone(), &[0]), vec![0]);
+        assert_eq!(outputs(position, &[5]), vec![1]);
+        assert_eq!(outputs(immediate.clone(), &[0]), vec![0]);
+        assert_eq!(outputs(immediate, &[-5]), vec![1]);
+    }
+
+    #[test]
+    fn day_5_larger_comparison_example() {
+        let program = vec![
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31,
+            1106, 0, 36, 98, 0, 0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104,
+            999, 1105, 1, 46, 1101, 1000, 1, 20, 4, 20, 1105, 1, 46, 98, 99,
+        ];
+
+        assert_eq!(outputs(program.clone(), &[7]), vec![999]);
+        assert_eq!(outputs(program.clone(), &[8]), vec![1000]);
+        assert_eq!(outputs(program, &[9]), vec![1001]);
+    }
+
+    #[test]
+    fn day_9_quine() {
+        let program = vec![109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99];
+        assert_eq!(outputs(program.clone(), &[]), program);
+    }
+
+    #[test]
+    fn day_9_sixteen_digit_output() {
+        let output = outputs(vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0], &[]);
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].to_string().len(), 16);
+    }
+
+    #[test]
+    fn day_9_large_number_output() {
+        assert_eq!(outputs(vec![104, 1125899906842624, 99], &[]), vec![1125899906842624]);
+    }
+
+    #[test]
+    fn day_2_puzzle_input() {
+        let mut machine = Machine::new_from_file("src/inputs/02A.txt");
+        machine.memory[1] = 12;
+        machine.memory[2] = 2;
+        machine.run_program();
+
+        assert_eq!(machine.memory[0], 11590668);
+    }
+
+    #[test]
+    fn day_5_puzzle_input() {
+        let mut machine = Machine::new_from_file("src/inputs/05A.txt");
+        machine.input_buffer.push_back(1);
+        assert_eq!(machine.run_program(), MachineState::Halted);
+
+        let diagnostics: Vec<i128> = machine.output_buffer.into_iter().collect();
+        let (code, checks) = diagnostics.split_last().unwrap();
+        assert!(checks.iter().all(|x| *x == 0));
+        assert_eq!(*code, 10987514);
+
+        let mut machine = Machine::new_from_file("src/inputs/05A.txt");
+        machine.input_buffer.push_back(5);
+        machine.run_program();
+        assert_eq!(machine.output_buffer, vec![14195011]);
+    }
+
+    #[test]
+    fn day_9_puzzle_input() {
+        let mut machine = Machine::new_from_file("src/inputs/09A.txt");
+        machine.input_buffer.push_back(1);
+        machine.run_program();
+        assert_eq!(machine.output_buffer, vec![2890527621]);
+
+        let mut machine = Machine::new_from_file("src/inputs/09A.txt");
+        machine.input_buffer.push_back(2);
+        machine.run_program();
+        assert_eq!(machine.output_buffer, vec![66772]);
+    }
+}
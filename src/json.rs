@@ -0,0 +1,31 @@
+/// Escapes a value so it can be placed between quotes in a JSON string
+pub fn escape(value: &str) -> String {
+    let mut result = String::new();
+    for ch in value.chars() {
+        match ch {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            x if (x as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", x as u32)),
+            x => result.push(x),
+        }
+    }
+
+    result
+}
+
+/// Formats a value as a quoted JSON string
+pub fn string(value: &str) -> String {
+    format!("\"{}\"", escape(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(string("a \"b\"\\\n\u{1b}[2J"), "\"a \\\"b\\\"\\\\\\n\\u001b[2J\"");
+    }
+}
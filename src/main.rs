@@ -4,10 +4,37 @@ extern crate regex;
 extern crate console;
 extern crate num_format;
 
-use std::time::SystemTime;
-use num_format::{Locale, ToFormattedString};
+use answers::{Answers, Verdict};
+use bench::{Baseline, Comparison, Stats};
+use cli::{BenchOptions, BreakoutMode, Command, DaySelection, ImageOptions, OutputFormat, PasswordOptions, RunOptions, SifConversion, SifOptions,
+          WiresOptions};
+use image::Image;
+use input::InputSource;
+use puzzle::{PartError, Puzzle};
+use puzzle_03::Day03;
+use puzzle_04::rules::RuleSet;
+use puzzle_08::convert;
+use puzzle_08::sif::SifImage;
+use registry::PuzzleEntry;
+use report::{JsonReporter, Reporter, RunError, TextReporter};
+use std::env;
+use std::fs;
+use std::process;
 
+mod answers;
+mod bench;
+mod cli;
+mod deflate;
+mod display;
+mod geometry;
+mod gif;
+mod image;
+mod input;
 mod intcode;
+mod json;
+mod ocr;
+mod png;
+mod puzzle;
 mod puzzle_01;
 mod puzzle_02;
 mod puzzle_03;
@@ -24,15 +51,318 @@ mod puzzle_13;
 mod puzzle_14;
 mod puzzle_15;
 mod puzzle_16;
+mod puzzle_17;
 mod puzzle_18;
 mod puzzle_19;
+mod registry;
+mod report;
+mod search;
 
 fn main() {
-    let now = SystemTime::now();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match cli::parse_args(&args) {
+        Ok(x) => x,
+        Err(message) => {
+            eprintln!("{}", message);
+            eprintln!();
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
 
-    puzzle_19::run();
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::List => list_puzzles(),
+        Command::Run(options) => {
+            if run_puzzles(&options).is_err() {
+                process::exit(1);
+            }
+        }
 
-    let elapsed = now.elapsed().unwrap();
-    let elapsted_ms = elapsed.as_millis();
-    println!("Run completed in {}ms", elapsted_ms.to_formatted_string(&Locale::en));
+        Command::Bench(options) => {
+            if let Err(message) = bench_puzzles(&options) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+
+        Command::Image(options) => {
+            if let Err(message) = export_image(&options) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+
+        Command::Sif(options) => {
+            if let Err(message) = convert_sif(&options) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+
+        Command::Wires(options) => {
+            if let Err(message) = report_wires(&options) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+
+        Command::Passwords(options) => {
+            if let Err(message) = list_passwords(&options) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+
+        Command::Breakout(mode) => {
+            if let Err(message) = play_breakout(mode) {
+                eprintln!("{}", message);
+                process::exit(1);
+            }
+        }
+    }
+}
+
+fn list_puzzles() {
+    for puzzle in registry::puzzles() {
+        println!("Day {:02}: {}", puzzle.day, puzzle.title);
+    }
+}
+
+fn run_puzzles(options: &RunOptions) -> Result<(), ()> {
+    let mut reporter: Box<dyn Reporter> = match options.format {
+        OutputFormat::Text => Box::new(TextReporter::new(options.days == DaySelection::All)),
+        OutputFormat::Json => Box::new(JsonReporter),
+    };
+
+    match solve_puzzles(options, reporter.as_mut()) {
+        Ok(()) => Ok(()),
+        Err(error) => {
+            reporter.error(&error);
+            Err(())
+        }
+    }
+}
+
+fn solve_puzzles(options: &RunOptions, reporter: &mut dyn Reporter) -> Result<(), RunError> {
+    // Known answers only apply to the inputs stored with the repository
+    let answers = match options.input {
+        None => Answers::load(answers::ANSWERS_PATH).map_err(RunError::Answers)?,
+        Some(_) => Answers::empty(),
+    };
+
+    let parts = match options.part {
+        None => vec![1, 2],
+        Some(part) => vec![part],
+    };
+
+    let puzzles = match options.days {
+        DaySelection::All => registry::puzzles(),
+        DaySelection::Single(day) => match registry::find(day) {
+            None => return Err(RunError::UnknownDay(day)),
+            Some(x) => vec![x],
+        },
+    };
+
+    let mut verdicts = Vec::new();
+    for puzzle in puzzles {
+        let source = match &options.input {
+            None => InputSource::for_day(puzzle.day),
+            Some(x) => x.clone(),
+        };
+
+        reporter.day_started(&puzzle);
+        verdicts.extend(run_puzzle(&puzzle, &source, &parts, &answers, reporter)?);
+    }
+
+    reporter.run_finished(&verdicts);
+
+    let mismatches = verdicts.iter()
+        .filter(|x| matches!(x, Verdict::Regression { .. } | Verdict::Fail { .. }))
+        .count();
+
+    if mismatches > 0 {
+        return Err(RunError::Mismatch(mismatches));
+    }
+
+    Ok(())
+}
+
+/// Solves the requested parts, reporting each answer and how it compares to the known answer
+fn run_puzzle(puzzle: &PuzzleEntry,
+              source: &InputSource,
+              parts: &[u8],
+              answers: &Answers,
+              reporter: &mut dyn Reporter) -> Result<Vec<Verdict>, RunError> {
+    let input = source.load().map_err(RunError::Input)?;
+    let solution = (puzzle.solve)(&input, parts);
+    let mut elapsed = solution.parse_elapsed;
+    let mut verdicts = Vec::new();
+
+    for answer in &solution.answers {
+        elapsed += answer.elapsed;
+        let verdict = answers.check(puzzle.day, answer.part, &answer.answer);
+        reporter.part_solved(puzzle.day, answer, &verdict);
+        verdicts.push(verdict);
+    }
+
+    reporter.day_finished(puzzle.day, elapsed, &verdicts);
+    Ok(verdicts)
+}
+
+fn bench_puzzles(options: &BenchOptions) -> Result<(), String> {
+    let puzzles = match options.days {
+        DaySelection::All => registry::puzzles(),
+        DaySelection::Single(day) => match registry::find(day) {
+            None => return Err(format!("Day {} does not have a solution", day)),
+            Some(x) => vec![x],
+        },
+    };
+
+    let mut baseline = Baseline::load(&options.baseline)?;
+    let mut regressions = 0;
+    let mut failures = 0;
+
+    println!("{} iterations per stage, times in ms", options.iterations);
+    println!("{:<6} {:<6} {:>10} {:>10} {:>10} {:>10} {:>10}  Compared to baseline",
+             "Day", "Stage", "Mean", "Median", "Stddev", "Min", "Max");
+
+    for puzzle in puzzles {
+        let input = InputSource::for_day(puzzle.day).load()?;
+        for (stage, samples) in (puzzle.bench)(&input, options.iterations) {
+            let day = format!("{:02}", puzzle.day);
+            let samples = match samples {
+                Ok(x) => x,
+                Err(PartError::NotApplicable(reason)) => {
+                    println!("{:<6} {:<6} not applicable: {}", day, stage, reason);
+                    continue;
+                }
+
+                Err(PartError::Panic(message)) => {
+                    println!("{:<6} {:<6} FAILED: {}", day, stage, message);
+                    failures += 1;
+                    continue;
+                }
+            };
+
+            let stats = Stats::from_samples(&samples);
+            let comparison = baseline.compare(puzzle.day, stage, &stats);
+            if let Comparison::Regressed { .. } = comparison {
+                regressions += 1;
+            }
+
+            println!("{:<6} {:<6} {:>10.3} {:>10.3} {:>10.3} {:>10.3} {:>10.3}  {}",
+                     day, stage, stats.mean, stats.median, stats.stddev, stats.min, stats.max,
+                     comparison);
+
+            if options.save_baseline {
+                baseline.set(puzzle.day, stage, stats.median);
+            }
+        }
+    }
+
+    if options.save_baseline {
+        baseline.save(&options.baseline)?;
+        println!("Saved baseline to {}", options.baseline);
+    }
+
+    if failures > 0 {
+        return Err(format!("{} stage(s) failed", failures));
+    } else if regressions > 0 && !options.save_baseline {
+        return Err(format!("{} stage(s) are more than {}% slower than the baseline",
+                           regressions, bench::REGRESSION_THRESHOLD * 100.0));
+    }
+
+    Ok(())
+}
+
+fn export_image(options: &ImageOptions) -> Result<(), String> {
+    let puzzle = match registry::find(options.day) {
+        None => return Err(format!("Day {} does not have a solution", options.day)),
+        Some(x) => x,
+    };
+
+    let render = match puzzle.image {
+        None => return Err(format!("Day {} does not have an image to draw", options.day)),
+        Some(x) => x,
+    };
+
+    let source = match &options.input {
+        None => InputSource::for_day(puzzle.day),
+        Some(x) => x.clone(),
+    };
+
+    let image = render(&source.load()?);
+    fs::write(&options.output, image.encode(options.format, options.scale))
+        .map_err(|error| format!("Could not write {}: {}", options.output, error))?;
+
+    println!("Saved a {}x{} image of day {} to {}", image.width() * options.scale, image.height() * options.scale,
+             puzzle.day, options.output);
+    Ok(())
+}
+
+fn convert_sif(options: &SifOptions) -> Result<(), String> {
+    let data = fs::read(&options.input).map_err(|error| format!("Could not read {}: {}", options.input, error))?;
+    let (output, summary) = match options.conversion {
+        SifConversion::Encode { layers, seed } => {
+            let bitmap = Image::decode(&data)?.to_bitmap();
+            let sif = convert::split_into_layers(&bitmap, layers, seed);
+            let summary = format!("a {}x{} image with {} layer(s)", sif.width(), sif.height(), layers);
+            (sif.encode().into_bytes(), summary)
+        }
+
+        SifConversion::Decode { width, height, format, scale } => {
+            let text = String::from_utf8_lossy(&data);
+            let sif = SifImage::decode(&text, width, height)?;
+            let summary = format!("a {}x{} image flattened from {} layer(s)", width * scale, height * scale, sif.layers().len());
+            (convert::to_image(&sif)?.encode(format, scale), summary)
+        }
+    };
+
+    fs::write(&options.output, output).map_err(|error| format!("Could not write {}: {}", options.output, error))?;
+    println!("Saved {} to {}", summary, options.output);
+    Ok(())
+}
+
+fn report_wires(options: &WiresOptions) -> Result<(), String> {
+    let source = match &options.input {
+        None => InputSource::for_day(3),
+        Some(x) => x.clone(),
+    };
+
+    let wires = Day03::parse(&source.load()?);
+    print!("{}", puzzle_03::report(&wires, options.min_wires, options.metric));
+
+    if let Some(path) = &options.svg {
+        fs::write(path, puzzle_03::render_svg(&wires, options.metric))
+            .map_err(|error| format!("Could not write {}: {}", path, error))?;
+        println!("Saved a picture of {} wires to {}", wires.len(), path);
+    }
+
+    Ok(())
+}
+
+fn list_passwords(options: &PasswordOptions) -> Result<(), String> {
+    let rules = RuleSet::parse(&options.rules)?;
+    let count = rules.count(options.min, options.max);
+    println!("{} password(s) from {} to {}", count, options.min, options.max);
+    for password in rules.candidates(options.min, options.max).take(options.limit) {
+        println!("{}", password);
+    }
+
+    Ok(())
+}
+
+fn play_breakout(mode: BreakoutMode) -> Result<(), String> {
+    match mode {
+        BreakoutMode::Headless => puzzle_13::run_headless(),
+        BreakoutMode::Watch => puzzle_13::run(),
+        BreakoutMode::Keyboard => puzzle_13::run_with_keyboard(),
+        BreakoutMode::Compare => puzzle_13::compare_controllers(),
+        BreakoutMode::Record => puzzle_13::record_replay()?,
+        BreakoutMode::Memory => puzzle_13::print_memory_layout()?,
+        BreakoutMode::Wall => puzzle_13::run_with_paddle_wall()?,
+    }
+
+    Ok(())
 }
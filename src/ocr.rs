@@ -0,0 +1,147 @@
+use crate::geometry::{Grid, Point};
+
+/// The 4x6 letters most puzzles draw, in cells 5 columns wide.  `Y` is 5 wide and `I` is 3.
+pub const SMALL_GLYPHS: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The 6x10 letters used by the larger messages
+const LARGE_GLYPHS: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Reads the capital letters drawn in an image, ignoring any blank border around them
+pub fn recognize<T, F>(image: &Grid<T>, is_lit: F) -> Result<String, String> where F: Fn(&T) -> bool {
+    let lit = |x: usize, y: usize| is_lit(image.get(&Point::new(x, y)).unwrap());
+    let rows = (0..image.height()).filter(|y| (0..image.width()).any(|x| lit(x, *y))).collect::<Vec<usize>>();
+    if rows.is_empty() {
+        return Err("The image is blank".to_owned());
+    }
+
+    let top = rows[0];
+    let height = rows[rows.len() - 1] - top + 1;
+    let mut glyphs: Vec<(char, Vec<&str>)> = match height {
+        6 => SMALL_GLYPHS.iter().map(|(letter, rows)| (*letter, rows.to_vec())).collect(),
+        10 => LARGE_GLYPHS.iter().map(|(letter, rows)| (*letter, rows.to_vec())).collect(),
+        x => return Err(format!("Letters are {} rows tall, but only 6 and 10 row letters can be read", x)),
+    };
+
+    let column_is_blank = |x: usize| (top..top + height).all(|y| !lit(x, y));
+    let matches = |glyph: &Vec<&str>, left: usize| {
+        glyph.iter().enumerate().all(|(row, pattern)| {
+            pattern.chars().enumerate().all(|(column, ch)| {
+                let x = left + column;
+                x < image.width() && lit(x, top + row) == (ch == '#')
+            })
+        })
+    };
+
+    // Letters can touch when one fills its whole cell, so wider letters are tried first
+    glyphs.sort_by_key(|(_, rows)| std::cmp::Reverse(rows[0].len()));
+
+    let mut text = String::new();
+    let mut x = 0;
+    while x < image.width() {
+        if column_is_blank(x) {
+            x += 1;
+            continue;
+        }
+
+        match glyphs.iter().find(|(_, glyph)| matches(glyph, x)) {
+            None => {
+                let rendered = image.render(|cell| if is_lit(cell) { '█' } else { ' ' });
+                return Err(format!("Unrecognized letter at column {} of:\n{}", x, rendered));
+            }
+
+            Some((letter, glyph)) => {
+                text.push(*letter);
+                x += glyph[0].len();
+            }
+        }
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_small_letters() {
+        let image = Grid::parse("\
+..........................
+.###...##..###..###...##..
+.#..#.#..#.#..#.#..#.#..#.
+.#..#.#..#.#..#.#..#.#....
+.###..####.###..###..#....
+.#.#..#..#.#....#.#..#..#.
+.#..#.#..#.#....#..#..##..
+..........................", |ch| ch == '#');
+
+        assert_eq!(recognize(&image, |x| *x), Ok("RAPRC".to_owned()));
+    }
+
+    #[test]
+    fn reads_touching_and_narrow_letters() {
+        let image = Grid::parse("\
+#...#.##..###
+#...##..#..#.
+.#.#.#.....#.
+..#..#.##..#.
+..#..#..#..#.
+..#...###.###", |ch| ch == '#');
+
+        assert_eq!(recognize(&image, |x| *x), Ok("YGI".to_owned()));
+    }
+
+    #[test]
+    fn reads_large_letters() {
+        let rows = LARGE_GLYPHS.iter()
+            .find(|(letter, _)| *letter == 'X').unwrap().1.iter()
+            .zip(LARGE_GLYPHS.iter().find(|(letter, _)| *letter == 'N').unwrap().1.iter())
+            .map(|(x, n)| format!("{}..{}", x, n))
+            .collect::<Vec<String>>();
+
+        let image = Grid::parse(&rows.join("\n"), |ch| ch == '#');
+        assert_eq!(recognize(&image, |x| *x), Ok("XN".to_owned()));
+    }
+
+    #[test]
+    fn rejects_unknown_letters() {
+        let image = Grid::parse("#.#\n#.#\n###\n..#\n..#\n..#", |ch| ch == '#');
+        assert!(recognize(&image, |x| *x).unwrap_err().starts_with("Unrecognized letter at column 0"));
+        assert!(recognize(&Grid::parse("#\n#", |ch| ch == '#'), |x| *x).is_err());
+    }
+}
@@ -0,0 +1,405 @@
+use crate::deflate;
+use crate::display::Rgb;
+use crate::image;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// 8 bit RGB without interlacing
+const BIT_DEPTH: u8 = 8;
+const COLOR_TYPE_RGB: u8 = 2;
+
+const COLOR_TYPE_GRAY: u8 = 0;
+const COLOR_TYPE_PALETTE: u8 = 3;
+const COLOR_TYPE_GRAY_ALPHA: u8 = 4;
+const COLOR_TYPE_RGBA: u8 = 6;
+
+/// A decoded image's size and pixels, row by row
+pub struct Decoded {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+struct Header {
+    width: usize,
+    height: usize,
+    bit_depth: u8,
+    color_type: u8,
+}
+
+/// Encodes `width * height` pixels, row by row, as an RGB PNG image
+pub fn encode(width: usize, height: usize, pixels: &[Rgb]) -> Vec<u8> {
+    if pixels.len() != width * height {
+        panic!("Expected {} pixels for a {}x{} image, found {}", width * height, width, height, pixels.len());
+    }
+
+    let mut header = Vec::new();
+    push_u32(&mut header, width as u32);
+    push_u32(&mut header, height as u32);
+    header.extend_from_slice(&[BIT_DEPTH, COLOR_TYPE_RGB, 0, 0, 0]);
+
+    // Every row starts with its filter type, and no filtering is done
+    let mut raw = Vec::with_capacity(height * (width * 3 + 1));
+    for row in pixels.chunks(width.max(1)).take(height) {
+        raw.push(0);
+        for (red, green, blue) in row {
+            raw.extend_from_slice(&[*red, *green, *blue]);
+        }
+    }
+
+    let mut result = SIGNATURE.to_vec();
+    push_chunk(&mut result, b"IHDR", &header);
+    push_chunk(&mut result, b"IDAT", &zlib(&raw));
+    push_chunk(&mut result, b"IEND", &[]);
+    result
+}
+
+/// Decodes a non-interlaced PNG image of any color type and bit depth. Pixels with an alpha
+/// channel are drawn over black.
+pub fn decode(data: &[u8]) -> Result<Decoded, String> {
+    if data.len() < SIGNATURE.len() || data[..SIGNATURE.len()] != SIGNATURE {
+        return Err("Not a PNG image".to_owned());
+    }
+
+    let mut header = None;
+    let mut palette = Vec::new();
+    let mut compressed = Vec::new();
+    let mut position = SIGNATURE.len();
+    loop {
+        if position + 12 > data.len() {
+            return Err("PNG image ended before its IEND chunk".to_owned());
+        }
+
+        let length = read_u32(&data[position..]) as usize;
+        let end = position + 8 + length;
+        if end + 4 > data.len() {
+            return Err("PNG image ended in the middle of a chunk".to_owned());
+        }
+
+        let kind = &data[position + 4..position + 8];
+        let chunk = &data[position + 8..end];
+        if crc32(&data[position + 4..end]) != read_u32(&data[end..]) {
+            return Err(format!("Checksum mismatch in the {} chunk", String::from_utf8_lossy(kind)));
+        }
+
+        match kind {
+            b"IHDR" => header = Some(read_header(chunk)?),
+            b"PLTE" => {
+                if !chunk.len().is_multiple_of(3) {
+                    return Err(format!("PLTE chunk is {} bytes, which isn't a whole number of colors", chunk.len()));
+                }
+
+                palette = chunk.chunks(3).map(|x| (x[0], x[1], x[2])).collect();
+            }
+
+            b"IDAT" => compressed.extend_from_slice(chunk),
+            b"IEND" => break,
+            _ => (),
+        }
+
+        position = end + 4;
+    }
+
+    let header = match header {
+        None => return Err("PNG image has no IHDR chunk".to_owned()),
+        Some(x) => x,
+    };
+
+    let count = image::pixel_count(header.width, header.height)?;
+    let raw = unzlib(&compressed)?;
+    let bits_per_pixel = channels(header.color_type) * header.bit_depth as usize;
+    let rows = unfilter(&raw, header.width, header.height, bits_per_pixel)?;
+    let mut pixels = Vec::with_capacity(count);
+    for row in rows {
+        for x in 0..header.width {
+            let samples = read_samples(&row, x, &header);
+            pixels.push(to_rgb(&samples, &header, &palette)?);
+        }
+    }
+
+    Ok(Decoded { width: header.width, height: header.height, pixels })
+}
+
+fn read_header(chunk: &[u8]) -> Result<Header, String> {
+    if chunk.len() != 13 {
+        return Err("IHDR chunk must be 13 bytes".to_owned());
+    }
+
+    let header = Header {
+        width: read_u32(chunk) as usize,
+        height: read_u32(&chunk[4..]) as usize,
+        bit_depth: chunk[8],
+        color_type: chunk[9],
+    };
+
+    if header.width == 0 || header.height == 0 {
+        return Err(format!("A PNG image can't be {}x{}", header.width, header.height));
+    }
+
+    let allowed_depths: &[u8] = match header.color_type {
+        COLOR_TYPE_GRAY => &[1, 2, 4, 8, 16],
+        COLOR_TYPE_PALETTE => &[1, 2, 4, 8],
+        COLOR_TYPE_RGB | COLOR_TYPE_GRAY_ALPHA | COLOR_TYPE_RGBA => &[8, 16],
+        x => return Err(format!("Unknown PNG color type {}", x)),
+    };
+
+    if !allowed_depths.contains(&header.bit_depth) {
+        return Err(format!("Bit depth {} isn't allowed for color type {}", header.bit_depth, header.color_type));
+    }
+
+    if chunk[12] != 0 {
+        return Err("Interlaced PNG images aren't supported".to_owned());
+    }
+
+    Ok(header)
+}
+
+fn channels(color_type: u8) -> usize {
+    match color_type {
+        COLOR_TYPE_GRAY | COLOR_TYPE_PALETTE => 1,
+        COLOR_TYPE_GRAY_ALPHA => 2,
+        COLOR_TYPE_RGB => 3,
+        _ => 4,
+    }
+}
+
+/// Undoes the filter each row starts with, returning the rows without their filter bytes
+fn unfilter(raw: &[u8], width: usize, height: usize, bits_per_pixel: usize) -> Result<Vec<Vec<u8>>, String> {
+    let stride = width.checked_mul(bits_per_pixel).map(|x| x.div_ceil(8));
+    let (stride, size) = match stride.and_then(|x| Some((x, height.checked_mul(x + 1)?))) {
+        None => return Err(format!("A {}x{} image at {} bits per pixel is too big", width, height, bits_per_pixel)),
+        Some(x) => x,
+    };
+
+    let bytes_per_pixel = (bits_per_pixel / 8).max(1);
+    if raw.len() < size {
+        return Err(format!("Expected {} bytes of image data, found {}", size, raw.len()));
+    }
+
+    let mut rows: Vec<Vec<u8>> = Vec::with_capacity(height);
+    for y in 0..height {
+        let start = y * (stride + 1);
+        let filter = raw[start];
+        let mut row = raw[start + 1..start + 1 + stride].to_vec();
+        for x in 0..stride {
+            let left = if x >= bytes_per_pixel { row[x - bytes_per_pixel] } else { 0 };
+            let up = if y > 0 { rows[y - 1][x] } else { 0 };
+            let up_left = if y > 0 && x >= bytes_per_pixel { rows[y - 1][x - bytes_per_pixel] } else { 0 };
+            let predicted = match filter {
+                0 => 0,
+                1 => left,
+                2 => up,
+                3 => ((left as u16 + up as u16) / 2) as u8,
+                4 => paeth(left, up, up_left),
+                x => return Err(format!("Unknown PNG filter type {}", x)),
+            };
+
+            row[x] = row[x].wrapping_add(predicted);
+        }
+
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
+
+fn paeth(left: u8, up: u8, up_left: u8) -> u8 {
+    let estimate = left as i16 + up as i16 - up_left as i16;
+    let to_left = (estimate - left as i16).abs();
+    let to_up = (estimate - up as i16).abs();
+    let to_up_left = (estimate - up_left as i16).abs();
+    if to_left <= to_up && to_left <= to_up_left {
+        left
+    } else if to_up <= to_up_left {
+        up
+    } else {
+        up_left
+    }
+}
+
+/// The samples of the pixel at `x`, scaled to 8 bits except for palette indexes
+fn read_samples(row: &[u8], x: usize, header: &Header) -> Vec<u8> {
+    let count = channels(header.color_type);
+    match header.bit_depth {
+        8 => row[x * count..(x + 1) * count].to_vec(),
+        16 => (0..count).map(|index| row[(x * count + index) * 2]).collect(),
+        depth => {
+            let bit = x * depth as usize;
+            let max = (1u8 << depth) - 1;
+            let value = (row[bit / 8] >> (8 - depth as usize - bit % 8)) & max;
+            if header.color_type == COLOR_TYPE_PALETTE {
+                vec![value]
+            } else {
+                vec![(value as u16 * 255 / max as u16) as u8]
+            }
+        }
+    }
+}
+
+fn to_rgb(samples: &[u8], header: &Header, palette: &[Rgb]) -> Result<Rgb, String> {
+    let over_black = |value: u8, alpha: u8| (value as u16 * alpha as u16 / 255) as u8;
+    Ok(match header.color_type {
+        COLOR_TYPE_GRAY => (samples[0], samples[0], samples[0]),
+        COLOR_TYPE_RGB => (samples[0], samples[1], samples[2]),
+        COLOR_TYPE_GRAY_ALPHA => {
+            let value = over_black(samples[0], samples[1]);
+            (value, value, value)
+        }
+
+        COLOR_TYPE_RGBA => (over_black(samples[0], samples[3]), over_black(samples[1], samples[3]),
+                            over_black(samples[2], samples[3])),
+
+        _ => match palette.get(samples[0] as usize) {
+            None => return Err(format!("Palette index {} is out of range", samples[0])),
+            Some(x) => *x,
+        },
+    })
+}
+
+/// Wraps a deflate stream with the zlib header and checksum PNG images expect
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut result = vec![0x78, 0x01];
+    result.extend(deflate::compress(data));
+    push_u32(&mut result, adler32(data));
+    result
+}
+
+/// Unwraps a zlib stream, checking its header and checksum
+fn unzlib(data: &[u8]) -> Result<Vec<u8>, String> {
+    if data.len() < 6 || data[0] & 0x0F != 8 || !((data[0] as u16) << 8 | data[1] as u16).is_multiple_of(31) {
+        return Err("Image data isn't a valid zlib stream".to_owned());
+    }
+
+    let result = deflate::decompress(&data[2..data.len() - 4])?;
+    if adler32(&result) != read_u32(&data[data.len() - 4..]) {
+        return Err("Image data doesn't match its checksum".to_owned());
+    }
+
+    Ok(result)
+}
+
+fn push_chunk(output: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    push_u32(output, data.len() as u32);
+    let start = output.len();
+    output.extend_from_slice(kind);
+    output.extend_from_slice(data);
+    let checksum = crc32(&output[start..]);
+    push_u32(output, checksum);
+}
+
+fn push_u32(output: &mut Vec<u8>, value: u32) {
+    output.extend_from_slice(&value.to_be_bytes());
+}
+
+fn read_u32(data: &[u8]) -> u32 {
+    u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+}
+
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+pub fn adler32(data: &[u8]) -> u32 {
+    let mut a = 1u32;
+    let mut b = 0u32;
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(crc32(b"IEND"), 0xAE42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11E6_0398);
+    }
+
+    #[test]
+    fn encodes_chunks() {
+        let png = encode(2, 1, &[(255, 0, 0), (0, 0, 255)]);
+        assert_eq!(&png[0..8], &SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(&png[16..24], &[0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(&png[png.len() - 12..], &[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]);
+    }
+
+    #[test]
+    fn decodes_what_it_encodes() {
+        let pixels = vec![(255, 0, 0), (0, 0, 255), (1, 2, 3), (250, 250, 250), (9, 8, 7), (0, 0, 0)];
+        let decoded = decode(&encode(3, 2, &pixels)).unwrap();
+        assert_eq!((decoded.width, decoded.height), (3, 2));
+        assert_eq!(decoded.pixels, pixels);
+        assert!(decode(b"P1\n1 1\n0").is_err());
+    }
+
+    #[test]
+    fn rejects_partial_palette_colors() {
+        // Slips a 4 byte palette in after the IHDR chunk
+        let png = encode(1, 1, &[(0, 0, 0)]);
+        let mut broken = png[..33].to_vec();
+        push_chunk(&mut broken, b"PLTE", &[1, 2, 3, 4]);
+        broken.extend_from_slice(&png[33..]);
+
+        assert_eq!(decode(&broken).err(), Some("PLTE chunk is 4 bytes, which isn't a whole number of colors".to_owned()));
+    }
+
+    #[test]
+    fn rejects_empty_and_oversized_images() {
+        // Only the IHDR chunk's size is changed, and the image data is left for one pixel
+        let resized = |width: u32, height: u32| {
+            let png = encode(1, 1, &[(0, 0, 0)]);
+            let mut header = Vec::new();
+            push_u32(&mut header, width);
+            push_u32(&mut header, height);
+            header.extend_from_slice(&png[24..29]);
+
+            let mut result = SIGNATURE.to_vec();
+            push_chunk(&mut result, b"IHDR", &header);
+            result.extend_from_slice(&png[33..]);
+            result
+        };
+
+        assert_eq!(decode(&resized(0, 5)).err(), Some("A PNG image can't be 0x5".to_owned()));
+        assert_eq!(decode(&resized(3, 0)).err(), Some("A PNG image can't be 3x0".to_owned()));
+        assert!(decode(&resized(u32::MAX, u32::MAX)).is_err());
+        assert!(decode(&resized(1, 1)).is_ok());
+        assert!(unfilter(&[], usize::MAX, 2, 24).is_err());
+        assert!(unfilter(&[], usize::MAX / 8, 3, 8).is_err());
+    }
+
+    #[test]
+    fn decodes_gray_bits_and_filters() {
+        // 3x2, one bit per pixel
+        let gray = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+            0x52, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0xb5, 0x0f, 0x5b,
+            0xb7, 0x00, 0x00, 0x00, 0x0c, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0x58, 0xc0, 0xe0, 0x00, 0x00,
+            0x02, 0x24, 0x00, 0xe1, 0x4d, 0x16, 0xe8, 0x5e, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae,
+            0x42, 0x60, 0x82];
+        let decoded = decode(&gray).unwrap();
+        let lit: Vec<bool> = decoded.pixels.iter().map(|x| *x == (255, 255, 255)).collect();
+        assert_eq!(lit, vec![true, false, true, false, true, false]);
+
+        // 2x2 RGB with a Sub filtered row then a Paeth filtered row
+        let filtered = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44,
+            0x52, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02, 0x08, 0x02, 0x00, 0x00, 0x00, 0xfd, 0xd4, 0x9a,
+            0x73, 0x00, 0x00, 0x00, 0x13, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xe4, 0x12, 0x91, 0x03, 0x02,
+            0x16, 0x1b, 0x1b, 0x1b, 0x20, 0x05, 0x00, 0x0a, 0x56, 0x01, 0xaa, 0xa6, 0x8c, 0x93, 0x30, 0x00, 0x00,
+            0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82];
+        let decoded = decode(&filtered).unwrap();
+        assert_eq!(decoded.pixels, vec![(10, 20, 30), (40, 50, 60), (70, 80, 90), (100, 110, 120)]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::any::Any;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{Duration, Instant};
+
+/// A day's solution, split into parsing the input and solving each part from the parsed input
+pub trait Puzzle {
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> Self::Input;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+}
+
+/// Why a part has no answer
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum PartError {
+    /// Parsing or solving panicked, with the panic message
+    Panic(String),
+    /// The part can't be asked of this input, with the reason
+    NotApplicable(String),
+}
+
+/// Panic payload for a part that doesn't apply to the input it's given, raised by `not_applicable`
+pub struct NotApplicable(pub String);
+
+pub struct PartAnswer {
+    pub part: u8,
+    pub answer: Result<String, PartError>,
+    pub elapsed: Duration,
+}
+
+pub struct Solution {
+    pub parse_elapsed: Duration,
+    pub answers: Vec<PartAnswer>,
+}
+
+/// Stops solving a part because the input doesn't allow it, so the runner can tell this apart from
+/// a failure
+pub fn not_applicable(reason: &str) -> ! {
+    panic::panic_any(NotApplicable(reason.to_owned()))
+}
+
+/// Parses the input once and then solves each requested part, timing every step.  A panic while
+/// parsing or solving is recorded against the affected parts instead of ending the run.
+pub fn solve<P: Puzzle>(input: &str, parts: &[u8]) -> Solution {
+    let now = Instant::now();
+    let parsed = parse::<P>(input);
+    let parse_elapsed = now.elapsed();
+
+    let parsed = match parsed {
+        Ok(x) => x,
+        Err(error) => {
+            let answers = parts.iter()
+                .map(|part| PartAnswer { part: *part, answer: Err(error.clone()), elapsed: Duration::from_secs(0) })
+                .collect();
+
+            return Solution { parse_elapsed, answers };
+        }
+    };
+
+    let mut answers = Vec::new();
+    for part in parts {
+        let now = Instant::now();
+        let answer = catch(|| match part {
+            1 => P::part1(&parsed).to_string(),
+            2 => P::part2(&parsed).to_string(),
+            x => panic!("Puzzles only have parts 1 and 2, not {}", x),
+        });
+
+        answers.push(PartAnswer {
+            part: *part,
+            answer,
+            elapsed: now.elapsed(),
+        });
+    }
+
+    Solution { parse_elapsed, answers }
+}
+
+/// Parses the input, with a panic's message saying it came from parsing
+pub fn parse<P: Puzzle>(input: &str) -> Result<P::Input, PartError> {
+    catch(|| P::parse(input)).map_err(|error| match error {
+        PartError::Panic(message) => PartError::Panic(format!("Parsing failed: {}", message)),
+        x => x,
+    })
+}
+
+/// Runs `f`, turning a panic into the error it stands for
+pub fn catch<T, F: FnOnce() -> T>(f: F) -> Result<T, PartError> {
+    silence_not_applicable();
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(part_error)
+}
+
+/// Parts that don't apply aren't errors, so they skip the default hook's panic message
+fn silence_not_applicable() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if info.payload().downcast_ref::<NotApplicable>().is_none() {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+fn part_error(payload: Box<dyn Any + Send>) -> PartError {
+    match payload.downcast::<NotApplicable>() {
+        Ok(reason) => PartError::NotApplicable(reason.0),
+        Err(payload) => PartError::Panic(panic_message(payload)),
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Unknown panic".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Halves;
+
+    impl Puzzle for Halves {
+        type Input = i32;
+        type Part1 = i32;
+        type Part2 = i32;
+
+        fn parse(input: &str) -> i32 {
+            input.parse().unwrap()
+        }
+
+        fn part1(value: &i32) -> i32 {
+            if value % 2 != 0 {
+                not_applicable("Odd numbers can't be halved");
+            }
+
+            value / 2
+        }
+
+        fn part2(value: &i32) -> i32 {
+            100 / value
+        }
+    }
+
+    #[test]
+    fn records_failed_and_not_applicable_parts() {
+        let answers: Vec<Result<String, PartError>> = solve::<Halves>("4", &[1, 2]).answers.into_iter().map(|x| x.answer).collect();
+        assert_eq!(answers, vec![Ok("2".to_owned()), Ok("25".to_owned())]);
+
+        let answers: Vec<Result<String, PartError>> = solve::<Halves>("0", &[1, 2]).answers.into_iter().map(|x| x.answer).collect();
+        assert_eq!(answers, vec![Ok("0".to_owned()), Err(PartError::Panic("attempt to divide by zero".to_owned()))]);
+
+        let answers = solve::<Halves>("3", &[1]).answers;
+        assert_eq!(answers[0].answer, Err(PartError::NotApplicable("Odd numbers can't be halved".to_owned())));
+
+        let answers = solve::<Halves>("x", &[2]).answers;
+        assert!(matches!(&answers[0].answer, Err(PartError::Panic(x)) if x.starts_with("Parsing failed")));
+    }
+}
@@ -1,28 +1,42 @@
-use std::fs::File;
-use std::io::{BufReader, BufRead};
-
-pub fn run() {
-    let file = File::open("src/inputs/01A.txt").unwrap();
-    let reader = BufReader::new(file);
-    let mut total_fuel = 0;
-    for line in reader.lines() {
-        let mass = line.unwrap().parse::<i32>().unwrap();
-        let mut fuel = calculate_fuel(mass);
-        total_fuel = total_fuel + fuel;
-
-        loop {
-            fuel = calculate_fuel(fuel);
-            if fuel <= 0 {
-                break;
-            }
+use crate::puzzle::Puzzle;
 
-            total_fuel = total_fuel + fuel;
-        }
+pub struct Day01;
+
+impl Puzzle for Day01 {
+    type Input = Vec<i32>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Vec<i32> {
+        input.lines()
+            .map(|line| line.trim().parse::<i32>().unwrap())
+            .collect()
     }
 
-    println!("Fuel for modules: {}", total_fuel);
+    fn part1(masses: &Vec<i32>) -> i32 {
+        masses.iter().map(|mass| calculate_fuel(*mass)).sum()
+    }
+
+    fn part2(masses: &Vec<i32>) -> i32 {
+        let mut total_fuel = 0;
+        for mass in masses {
+            let mut fuel = calculate_fuel(*mass);
+            total_fuel += fuel;
+
+            loop {
+                fuel = calculate_fuel(fuel);
+                if fuel <= 0 {
+                    break;
+                }
+
+                total_fuel += fuel;
+            }
+        }
+
+        total_fuel
+    }
 }
 
 fn calculate_fuel(mass: i32) -> i32 {
     mass / 3 - 2
-}
\ No newline at end of file
+}
@@ -1,19 +1,39 @@
-use crate::intcode::{Machine};
-
-pub fn run() {
-    for noun in 0..=99 {
-        for verb in 0..=99 {
-            let mut machine = Machine::new_from_file("src/inputs/02A.txt");
-            machine.memory[1] = noun;
-            machine.memory[2] = verb;
-
-            machine.run_program();
-            if machine.memory[0] == 19690720 {
-                println!("Result {}", 100 * noun + verb);
-                break;
+use crate::intcode::{parse_program, Machine};
+use crate::puzzle::Puzzle;
+
+pub struct Day02;
+
+impl Puzzle for Day02 {
+    type Input = Vec<i128>;
+    type Part1 = i128;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Vec<i128>) -> i128 {
+        run_with(program, 12, 2)
+    }
+
+    fn part2(program: &Vec<i128>) -> i128 {
+        for noun in 0..=99 {
+            for verb in 0..=99 {
+                if run_with(program, noun, verb) == 19690720 {
+                    return 100 * noun + verb;
+                }
             }
         }
+
+        panic!("No noun and verb combination produces 19690720");
     }
+}
+
+fn run_with(program: &[i128], noun: i128, verb: i128) -> i128 {
+    let mut machine = Machine::new_from_memory(program.to_vec());
+    machine.memory[1] = noun;
+    machine.memory[2] = verb;
 
-    println!("Finished");
-}
\ No newline at end of file
+    machine.run_program();
+    machine.memory[0]
+}
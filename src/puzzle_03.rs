@@ -1,109 +1,512 @@
-use std::collections::{HashSet};
-use std::fs::File;
-use std::io::Read;
+use crate::display::Rgb;
+use crate::geometry::{BoundingBox, Direction, Point};
+use crate::image;
+use crate::puzzle::Puzzle;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
-const START_X: i32 = 0;
-const START_Y: i32 = 0;
+/// A straight run of wire
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Segment {
+    pub start: Point<i32>,
+    pub end: Point<i32>,
+    /// Steps the wire takes before reaching the start of the segment
+    pub steps: i32,
+}
 
-#[derive(Eq, PartialEq, Clone, Hash)]
-struct Point {
-    pub x: i32,
-    pub y: i32,
+pub struct Wire {
+    pub segments: Vec<Segment>,
 }
 
-struct Wire {
-    pub set: HashSet<Point>,
-    pub list: Vec<Point>,
+/// A point two or more wires pass through
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Crossing {
+    pub point: Point<i32>,
+    /// The fewest steps each wire passing through the point takes to reach it, by wire index
+    pub steps: BTreeMap<usize, i32>,
 }
 
-pub fn run() {
-    let (line1, line2) = read_inputs();
-    let wire1 = build_points(line1);
-    let wire2 = build_points(line2);
+/// How close a crossing is to the central port
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Metric { Manhattan, Walk }
+
+/// Cycled through for each wire in the picture
+const WIRE_COLORS: [Rgb; 6] = [(31, 119, 180), (255, 127, 14), (44, 160, 44), (148, 103, 189), (140, 86, 75), (227, 119, 194)];
+const CROSSING_COLOR: Rgb = (214, 39, 40);
+/// Width of the longer side of the picture, whatever size the wires are
+const SVG_SIZE: i64 = 800;
+
+type Hits = HashMap<Point<i32>, BTreeMap<usize, i32>>;
+
+pub struct Day03;
+
+impl Puzzle for Day03 {
+    type Input = Vec<Wire>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    /// One wire per line, with at least two wires
+    fn parse(input: &str) -> Vec<Wire> {
+        let wires: Vec<Wire> = input.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(build_wire)
+            .collect();
+
+        if wires.len() < 2 {
+            panic!("Expected at least two wires, found {}", wires.len());
+        }
 
-    let distance = calc_distance_of_closest_intersection(wire1, wire2);
-    println!("Closest distance: {}", distance);
+        wires
+    }
+
+    fn part1(wires: &Vec<Wire>) -> i32 {
+        calc_distance_of_closest_intersection(&pairwise_intersections(wires), calc_manhattan_distance).unwrap()
+    }
+
+    fn part2(wires: &Vec<Wire>) -> i32 {
+        calc_distance_of_closest_intersection(&pairwise_intersections(wires), calc_walk_distance).unwrap()
+    }
 }
 
-fn read_inputs() -> (String, String) {
-    let mut file = File::open("src/inputs/03A.txt").unwrap();
-    let mut content = String::new();
-    file.read_to_string(&mut content).unwrap();
+impl Metric {
+    pub fn calc(&self, crossing: &Crossing) -> i32 {
+        match self {
+            Metric::Manhattan => calc_manhattan_distance(crossing),
+            Metric::Walk => calc_walk_distance(crossing),
+        }
+    }
+}
+
+impl fmt::Display for Crossing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let wires: Vec<String> = self.steps.keys().map(|x| (x + 1).to_string()).collect();
+        write!(f, "({}, {}) crossed by wires {}: distance {}, {} steps", self.point.x, self.point.y, wires.join(", "),
+               calc_manhattan_distance(self), calc_walk_distance(self))
+    }
+}
+
+impl Segment {
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    /// The lowest and highest coordinate the segment covers along its own axis
+    fn span(&self) -> (i32, i32) {
+        let (first, second) = if self.is_horizontal() { (self.start.x, self.end.x) } else { (self.start.y, self.end.y) };
+        (first.min(second), first.max(second))
+    }
+
+    /// The point on the segment at a coordinate along its own axis
+    fn point_at(&self, position: i32) -> Point<i32> {
+        if self.is_horizontal() { Point::new(position, self.start.y) } else { Point::new(self.start.x, position) }
+    }
 
-    let mut lines = content.lines();
-    let first = lines.next().unwrap().to_owned();
-    let second = lines.next().unwrap().to_owned();
-    (first, second)
+    fn steps_to(&self, point: &Point<i32>) -> i32 {
+        self.steps + self.start.manhattan_distance(point)
+    }
 }
 
-fn build_points(raw_path: String) -> Wire {
-    let mut current_point = Point {x: START_X, y: START_Y};
-    let mut point_set = HashSet::new();
-    let mut point_list = Vec::new();
+pub fn build_wire(raw_path: &str) -> Wire {
+    let mut current_point = Point::origin();
+    let mut steps = 0;
+    let mut segments = Vec::new();
 
-    for path in raw_path.split(",") {
+    for path in raw_path.trim().split(",") {
         let (direction, raw_count) = path.split_at(1);
         let spaces_count = raw_count.parse::<i32>().unwrap();
+        let direction = match direction {
+            "U" => Direction::North,
+            "D" => Direction::South,
+            "R" => Direction::East,
+            "L" => Direction::West,
+            x => panic!("Unknown direction {}", x),
+        };
 
-        for _ in 1..=spaces_count {
-            let new_point = match direction {
-                "U" => Point {x: current_point.x, y: current_point.y + 1},
-                "D" => Point {x: current_point.x, y: current_point.y - 1},
-                "R" => Point {x: current_point.x + 1, y: current_point.y},
-                "L" => Point {x: current_point.x - 1, y: current_point.y},
-                x => panic!("Unknown direction {}", x),
-            };
+        // Empty moves don't reach any new points
+        if spaces_count == 0 {
+            continue;
+        }
 
-            point_set.insert(new_point.clone());
-            point_list.push(new_point.clone());
-            current_point = new_point;
+        let end = current_point.step_by(direction, spaces_count);
+        segments.push(Segment { start: current_point, end, steps });
+        steps += spaces_count;
+        current_point = end;
+    }
+
+    Wire { segments }
+}
+
+/// Every point, other than the origin, that two or more wires pass through, ordered by y then x.
+/// A wire crossing itself doesn't count.  Where wires run along each other only the ends of the
+/// shared stretch and its point closest to the origin are included, as both distance and steps
+/// change linearly along it, so no other point there can be closer by either metric.
+pub fn find_crossings(wires: &[Wire]) -> Vec<Crossing> {
+    let segments: Vec<(usize, Segment)> = wires.iter().enumerate()
+        .flat_map(|(index, wire)| wire.segments.iter().map(move |x| (index, *x)))
+        .collect();
+
+    let lines = group_by_line(&segments);
+    let mut hits = HashMap::new();
+    find_perpendicular_hits(&segments, &mut hits);
+    find_overlapping_hits(&segments, &lines, &mut hits);
+    settle_steps(&segments, &lines, &mut hits);
+
+    let mut result: Vec<Crossing> = hits.into_iter()
+        .filter(|(point, steps)| *point != Point::origin() && steps.len() >= 2)
+        .map(|(point, steps)| Crossing { point, steps })
+        .collect();
+
+    result.sort_by_key(|x| (x.point.y, x.point.x));
+    result
+}
+
+/// Every crossing of each pair of wires, so a point three wires meet at shows up three times
+pub fn pairwise_intersections(wires: &[Wire]) -> Vec<Crossing> {
+    let mut result = Vec::new();
+    for crossing in find_crossings(wires) {
+        let steps: Vec<(usize, i32)> = crossing.steps.iter().map(|(wire, steps)| (*wire, *steps)).collect();
+        for first in 0..steps.len() {
+            for second in (first + 1)..steps.len() {
+                let pair = vec![steps[first], steps[second]].into_iter().collect();
+                result.push(Crossing { point: crossing.point, steps: pair });
+            }
         }
     }
 
-    Wire {set: point_set, list: point_list}
+    result
+}
+
+/// Points at least `count` of the wires pass through
+pub fn points_crossed_by(wires: &[Wire], count: usize) -> Vec<Crossing> {
+    find_crossings(wires).into_iter()
+        .filter(|x| x.steps.len() >= count)
+        .collect()
+}
+
+/// Lists the pairwise intersections and the points at least `min_wires` wires meet at, each
+/// ordered by the metric, closest first
+pub fn report(wires: &[Wire], min_wires: usize, metric: Metric) -> String {
+    let sorted = |mut crossings: Vec<Crossing>| {
+        crossings.sort_by_key(|x| metric.calc(x));
+        crossings
+    };
+
+    let intersections = sorted(pairwise_intersections(wires));
+    let shared = sorted(points_crossed_by(wires, min_wires));
+    let mut result = format!("{} wires, {} pairwise intersection(s)\n", wires.len(), intersections.len());
+    for crossing in &intersections {
+        result.push_str(&format!("  {}\n", crossing));
+    }
+
+    result.push_str(&format!("{} point(s) crossed by {} or more wires\n", shared.len(), min_wires));
+    for crossing in &shared {
+        result.push_str(&format!("  {}\n", crossing));
+    }
+
+    match calc_distance_of_closest_intersection(&intersections, |x| metric.calc(x)) {
+        None => result.push_str("No wires cross\n"),
+        Some(x) => result.push_str(&format!("Closest intersection: {}\n", x)),
+    }
+
+    result
 }
 
-fn calc_distance_of_closest_intersection(wire1: Wire, wire2: Wire) -> i32 {
-    let mut closest_distance = Option::None;
+/// Sweeps across the x axis keeping the horizontal segments that cover the current x ordered by
+/// their y, so each vertical segment only visits the horizontal segments it actually crosses
+fn find_perpendicular_hits(segments: &[(usize, Segment)], hits: &mut Hits) {
+    // Horizontal segments start before and end after verticals at the same x are checked
+    const ADD: u8 = 0;
+    const CHECK: u8 = 1;
+    const REMOVE: u8 = 2;
+
+    let mut events = Vec::with_capacity(segments.len() * 2);
+    for (index, (_, segment)) in segments.iter().enumerate() {
+        let (low, high) = segment.span();
+        if segment.is_horizontal() {
+            events.push((low, ADD, index));
+            events.push((high, REMOVE, index));
+        } else {
+            events.push((segment.start.x, CHECK, index));
+        }
+    }
 
-    for point in &wire1.set {
-        if wire2.set.contains(point) {
-            //let distance = calc_manhattan_distance(START_X, START_Y, point.x, point.y);
-            let distance = calc_walk_distance(point, &wire1, &wire2);
-            if let Some(prev_distance) = closest_distance {
-                if prev_distance > distance {
-                    closest_distance = Some(distance);
+    events.sort();
+    let mut active: BTreeMap<i32, Vec<usize>> = BTreeMap::new();
+    for (x, kind, index) in events {
+        let (wire, segment) = &segments[index];
+        match kind {
+            ADD => active.entry(segment.start.y).or_default().push(index),
+            REMOVE => {
+                let row = active.get_mut(&segment.start.y).unwrap();
+                row.retain(|x| *x != index);
+                if row.is_empty() {
+                    active.remove(&segment.start.y);
+                }
+            }
+
+            _ => {
+                let (low, high) = segment.span();
+                for (y, row) in active.range(low..=high) {
+                    let point = Point::new(x, *y);
+                    for other in row {
+                        let (other_wire, other_segment) = &segments[*other];
+                        if other_wire != wire {
+                            record(hits, point, *wire, segment.steps_to(&point));
+                            record(hits, point, *other_wire, other_segment.steps_to(&point));
+                        }
+                    }
                 }
-            } else {
-                closest_distance = Some(distance);
             }
         }
     }
-
-    closest_distance.unwrap()
 }
 
-fn calc_manhattan_distance(first_x: i32, first_y: i32, second_x: i32, second_y: i32) -> i32 {
-    (first_x - second_x).abs() + (first_y - second_y).abs()
+/// Indexes of the segments lying along each line, keyed by whether the line is horizontal and its
+/// fixed coordinate, ordered by where they start along it
+fn group_by_line(segments: &[(usize, Segment)]) -> HashMap<(bool, i32), Vec<usize>> {
+    let mut lines: HashMap<(bool, i32), Vec<usize>> = HashMap::new();
+    for (index, (_, segment)) in segments.iter().enumerate() {
+        let line = if segment.is_horizontal() { segment.start.y } else { segment.start.x };
+        lines.entry((segment.is_horizontal(), line)).or_default().push(index);
+    }
+
+    for line in lines.values_mut() {
+        line.sort_by_key(|x| segments[*x].1.span());
+    }
+
+    lines
 }
 
-fn calc_walk_distance(point: &Point, wire1: &Wire, wire2: &Wire) -> i32 {
-    let mut first_distance = 0;
-    let mut second_distance = 0;
+/// Finds where segments of different wires lie along the same line and records the ends of each
+/// shared stretch and its point closest to the origin
+fn find_overlapping_hits(segments: &[(usize, Segment)], lines: &HashMap<(bool, i32), Vec<usize>>, hits: &mut Hits) {
+    for line in lines.values() {
+        let mut active: Vec<usize> = Vec::new();
+        for index in line {
+            let (wire, segment) = &segments[*index];
+            let (low, high) = segment.span();
+            active.retain(|x| segments[*x].1.span().1 >= low);
+
+            for other in &active {
+                let (other_wire, other_segment) = &segments[*other];
+                if other_wire == wire {
+                    continue;
+                }
+
+                let end = high.min(other_segment.span().1);
+                for position in &[low, end, 0.max(low).min(end)] {
+                    let point = segment.point_at(*position);
+                    record(hits, point, *wire, segment.steps_to(&point));
+                    record(hits, point, *other_wire, other_segment.steps_to(&point));
+                }
+            }
 
-    for x in 0..wire1.list.len() {
-        if wire1.list[x] == *point {
-            first_distance = x as i32 + 1;
-            break;
+            active.push(*index);
         }
     }
+}
+
+/// A wire may reach a point sooner along a segment that runs through it than along the one it was
+/// found on, or pass through it without being recorded when it runs along the middle of a shared
+/// stretch, so every segment through each point is checked
+fn settle_steps(segments: &[(usize, Segment)], lines: &HashMap<(bool, i32), Vec<usize>>, hits: &mut Hits) {
+    for (point, steps) in hits.iter_mut() {
+        for (key, position) in &[((true, point.y), point.x), ((false, point.x), point.y)] {
+            let line = match lines.get(key) {
+                Some(x) => x,
+                None => continue,
+            };
 
-    for x in 0..wire2.list.len() {
-        if wire2.list[x] == *point {
-            second_distance = x as i32 + 1;
-            break;
+            let reaching = line.iter()
+                .map(|x| &segments[*x])
+                .take_while(|(_, segment)| segment.span().0 <= *position)
+                .filter(|(_, segment)| segment.span().1 >= *position);
+            for (wire, segment) in reaching {
+                let step = steps.entry(*wire).or_insert(i32::MAX);
+                *step = (*step).min(segment.steps_to(point));
+            }
         }
     }
+}
+
+/// Draws each wire as a line from the origin, with every crossing circled and the closest
+/// pairwise intersection by the metric labelled with its distance
+pub fn render_svg(wires: &[Wire], metric: Metric) -> String {
+    let paths: Vec<Vec<Point<i32>>> = wires.iter()
+        .map(|wire| std::iter::once(Point::origin()).chain(wire.segments.iter().map(|x| x.end)).collect())
+        .collect();
+
+    let bounds = BoundingBox::from_points(paths.iter().flatten()).unwrap();
+    let width = bounds.max.x as i64 - bounds.min.x as i64;
+    let height = bounds.max.y as i64 - bounds.min.y as i64;
+    let extent = width.max(height).max(1);
+    let margin = extent / 20 + 1;
 
-    first_distance + second_distance
-}
\ No newline at end of file
+    // Markers and text are sized from the picture so they stay visible on long wires
+    let radius = extent / 80 + 1;
+    let mut result = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\">\n",
+                             (width + margin * 2) * SVG_SIZE / (extent + margin * 2),
+                             (height + margin * 2) * SVG_SIZE / (extent + margin * 2),
+                             bounds.min.x as i64 - margin, bounds.min.y as i64 - margin,
+                             width + margin * 2, height + margin * 2);
+
+    result.push_str("  <rect x=\"-100%\" y=\"-100%\" width=\"300%\" height=\"300%\" fill=\"#ffffff\"/>\n");
+    for (index, path) in paths.iter().enumerate() {
+        let points: Vec<String> = path.iter().map(|x| format!("{},{}", x.x, x.y)).collect();
+        result.push_str(&format!("  <polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\" \
+                                  vector-effect=\"non-scaling-stroke\"><title>Wire {}</title></polyline>\n",
+                                 points.join(" "), image::hex_color(WIRE_COLORS[index % WIRE_COLORS.len()]), index + 1));
+    }
+
+    let crossings = find_crossings(wires);
+    for crossing in &crossings {
+        result.push_str(&format!("  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\" \
+                                  vector-effect=\"non-scaling-stroke\"><title>{}</title></circle>\n",
+                                 crossing.point.x, crossing.point.y, radius, image::hex_color(CROSSING_COLOR), crossing));
+    }
+
+    result.push_str(&format!("  <circle cx=\"0\" cy=\"0\" r=\"{}\" fill=\"#000000\"><title>Central port</title></circle>\n", radius));
+    // Measured pair by pair, the same way as the puzzle answers and the report
+    if let Some(closest) = pairwise_intersections(wires).iter().min_by_key(|x| metric.calc(x)) {
+        let (x, y) = (closest.point.x as i64, closest.point.y as i64);
+        result.push_str(&format!("  <circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                                 x, y, radius, image::hex_color(CROSSING_COLOR)));
+        result.push_str(&format!("  <text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" fill=\"{}\">{}</text>\n",
+                                 x + radius * 2, y - radius * 2, radius * 4, image::hex_color(CROSSING_COLOR),
+                                 metric.calc(closest)));
+    }
+
+    result.push_str("</svg>\n");
+    result
+}
+
+/// Keeps the fewest steps the wire has taken to reach the point
+fn record(hits: &mut Hits, point: Point<i32>, wire: usize, steps: i32) {
+    let entry = hits.entry(point).or_default().entry(wire).or_insert(steps);
+    *entry = (*entry).min(steps);
+}
+
+fn calc_distance_of_closest_intersection<F>(crossings: &[Crossing], metric: F) -> Option<i32>
+    where F: Fn(&Crossing) -> i32 {
+    crossings.iter()
+        .map(metric)
+        .min()
+}
+
+/// Distance from the central port, where every wire starts
+fn calc_manhattan_distance(crossing: &Crossing) -> i32 {
+    crossing.point.manhattan_distance(&Point::origin())
+}
+
+/// Combined steps the wires take to first reach the crossing
+fn calc_walk_distance(crossing: &Crossing) -> i32 {
+    crossing.steps.values().sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closest(input: &str) -> (i32, i32) {
+        let wires = Day03::parse(input);
+        (Day03::part1(&wires), Day03::part2(&wires))
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(closest("R8,U5,L5,D3\nU7,R6,D4,L4"), (6, 30));
+        assert_eq!(closest("R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83"), (159, 610));
+        assert_eq!(closest("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7"),
+                   (135, 410));
+    }
+
+    #[test]
+    fn overlapping_wires() {
+        // Both wires run along y = 0 between x = 3 and x = 5
+        let crossings = find_crossings(&Day03::parse("R5,U2\nU1,R3,D1,R4"));
+        let points: Vec<Point<i32>> = crossings.iter().map(|x| x.point).collect();
+        assert_eq!(points, vec![Point::new(3, 0), Point::new(5, 0)]);
+        assert_eq!(crossings[0].steps.values().cloned().collect::<Vec<i32>>(), vec![3, 5]);
+        assert_eq!(closest("R5,U2\nU1,R3,D1,R4"), (3, 8));
+    }
+
+    #[test]
+    fn wire_reaching_a_crossing_twice() {
+        // The first wire passes (3, 0) along the stretch it shares with the second, then comes
+        // back across it from above
+        let crossings = find_crossings(&Day03::parse("R6,U1,L3,D2\nD1,R1,U1,R4"));
+        let crossing = crossings.iter().find(|x| x.point == Point::new(3, 0)).unwrap();
+        assert_eq!(crossing.steps.values().cloned().collect::<Vec<i32>>(), vec![3, 5]);
+        assert_eq!(closest("R6,U1,L3,D2\nD1,R1,U1,R4"), (1, 4));
+    }
+
+    #[test]
+    fn long_wires() {
+        assert_eq!(closest("R2000000,U1000000\nU500000,R3000000"), (2_500_000, 5_000_000));
+    }
+
+    #[test]
+    fn many_wires() {
+        // The first three wires meet at (2, 0), and the last runs along the second on y = -2
+        let wires = Day03::parse("R4\nU2,R2,D4\nD2,R2,U3\n\nL1,U2,R4");
+        assert_eq!(wires.len(), 4);
+
+        let shared = points_crossed_by(&wires, 3);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].point, Point::new(2, 0));
+        assert_eq!(calc_walk_distance(&shared[0]), 2 + 6 + 6);
+        assert_eq!(points_crossed_by(&wires, 4), vec![]);
+
+        let pairs = pairwise_intersections(&wires);
+        assert_eq!(pairs.iter().filter(|x| x.point == Point::new(2, 0)).count(), 3);
+        assert_eq!((Day03::part1(&wires), Day03::part2(&wires)), (2, 6));
+
+        let report = report(&wires, 3, Metric::Walk);
+        assert!(report.contains("1 point(s) crossed by 3 or more wires\n  (2, 0) crossed by wires 1, 2, 3: distance 2, 14 steps"));
+        assert!(report.ends_with("Closest intersection: 6\n"));
+    }
+
+    #[test]
+    fn reports_fewest_steps_along_a_shared_stretch() {
+        // The wires share y = 0 from x = 2 to x = 8, and the first wire comes back down x = 5,
+        // which the second wire reaches sooner along the stretch than the first wire's way down
+        let wires = Day03::parse("R10,U5,L5,D10\nU1,R2,D1,R6");
+        let points: Vec<Point<i32>> = find_crossings(&wires).iter().map(|x| x.point).collect();
+        assert_eq!(points, vec![Point::new(2, 0), Point::new(5, 0), Point::new(8, 0)]);
+
+        let report = report(&wires, 2, Metric::Walk);
+        assert!(report.starts_with("2 wires, 3 pairwise intersection(s)\n"));
+        assert!(report.contains("  (5, 0) crossed by wires 1, 2: distance 5, 12 steps\n"));
+        assert!(report.ends_with("Closest intersection: 6\n"));
+
+        // A third wire running along the middle of the stretch passes (5, 0) too
+        let wires = Day03::parse("R10,U5,L5,D10\nU1,R2,D1,R6\nD1,R4,U1,R2");
+        let crossing = find_crossings(&wires).into_iter().find(|x| x.point == Point::new(5, 0)).unwrap();
+        assert_eq!(crossing.steps.values().cloned().collect::<Vec<i32>>(), vec![5, 7, 7]);
+    }
+
+    #[test]
+    fn long_shared_stretch() {
+        let wires = Day03::parse("R1000000,U1\nR1000000");
+        let points: Vec<Point<i32>> = find_crossings(&wires).iter().map(|x| x.point).collect();
+        assert_eq!(points, vec![Point::new(1_000_000, 0)]);
+        assert_eq!((Day03::part1(&wires), Day03::part2(&wires)), (1_000_000, 2_000_000));
+    }
+
+    #[test]
+    fn draws_wires() {
+        let wires = Day03::parse("R8,U5,L5,D3\nU7,R6,D4,L4");
+        let svg = render_svg(&wires, Metric::Walk);
+        assert!(svg.contains("viewBox=\"-1 -8 10 9\""));
+        assert!(svg.contains("<polyline points=\"0,0 8,0 8,-5 3,-5 3,-2\" fill=\"none\" stroke=\"#1f77b4\""));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert_eq!(svg.matches("stroke=\"#d62728\"").count(), 2);
+        assert!(svg.contains("<circle cx=\"6\" cy=\"-5\" r=\"1\" fill=\"#d62728\"/>"));
+        assert!(svg.contains(">30</text>"));
+
+        // All three wires meet at (2, 0), but the closest pair of them is what gets labelled
+        let svg = render_svg(&Day03::parse("R4\nU1,R2,D2\nD1,R2,U2"), Metric::Walk);
+        assert!(svg.contains("<circle cx=\"2\" cy=\"0\" r=\"1\" fill=\"#d62728\"/>"));
+        assert!(svg.contains(">6</text>"));
+    }
+}
@@ -1,58 +1,42 @@
+use crate::puzzle::Puzzle;
+use self::rules::RuleSet;
 
-pub fn run() {
-    let count = calculate(347312, 805915);
-    println!("Count: {}", count);
-}
+pub mod rules;
 
-fn calculate(min: i32, max: i32) -> i32 {
-    let mut count = 0;
-    for x in min..max {
-        if is_valid(x) {
-            count = count + 1;
-        }
-    }
+const PART1_RULES: &str = "monotonic nondecreasing; run>=2 exists; length 6";
+const PART2_RULES: &str = "monotonic nondecreasing; run==2 exists; length 6";
 
-    count
-}
+pub struct Day04;
+
+impl Puzzle for Day04 {
+    type Input = (u64, u64);
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> (u64, u64) {
+        parse_range(input).unwrap_or_else(|message| panic!("{}", message))
+    }
 
-fn is_valid(number: i32) -> bool {
-    let mut double_count = 0;
-    let as_string = number.to_string();
-    let digits = vec![
-        as_string.get(0..1).unwrap().parse::<u8>().unwrap(),
-        as_string.get(1..2).unwrap().parse::<u8>().unwrap(),
-        as_string.get(2..3).unwrap().parse::<u8>().unwrap(),
-        as_string.get(3..4).unwrap().parse::<u8>().unwrap(),
-        as_string.get(4..5).unwrap().parse::<u8>().unwrap(),
-        as_string.get(5..6).unwrap().parse::<u8>().unwrap(),
-    ];
-
-    let mut last_digit = digits[0];
-    let mut current_dup_count = 0;
-    let mut has_set_of_two = false;
-
-    for current_digit in digits.into_iter().skip(1) {
-        if current_digit < last_digit {
-            return false;
-        }
-
-        if current_digit == last_digit {
-            current_dup_count = current_dup_count + 1;
-            double_count = double_count + 1;
-        } else {
-            if current_dup_count > 0 && current_dup_count == 1 {
-                has_set_of_two = true;
-            }
-
-            current_dup_count = 0;
-        }
-
-        last_digit = current_digit;
+    /// Digits never decrease and at least two adjacent digits match
+    fn part1(range: &(u64, u64)) -> u64 {
+        RuleSet::parse(PART1_RULES).unwrap().count(range.0, range.1)
     }
 
-    if current_dup_count > 0 && current_dup_count == 1 {
-        has_set_of_two = true;
+    /// As part 1, but some matching pair can't be part of a larger group
+    fn part2(range: &(u64, u64)) -> u64 {
+        RuleSet::parse(PART2_RULES).unwrap().count(range.0, range.1)
     }
+}
+
+/// A range in the form min-max
+pub fn parse_range(input: &str) -> Result<(u64, u64), String> {
+    let parts: Vec<&str> = input.trim().split('-').collect();
+    match parts.as_slice() {
+        [min, max] => match (min.parse(), max.parse()) {
+            (Ok(min), Ok(max)) => Ok((min, max)),
+            _ => Err(format!("Expected a range of whole numbers, found '{}'", input.trim())),
+        },
 
-    has_set_of_two
-}
\ No newline at end of file
+        _ => Err(format!("Expected a range in the form min-max, found '{}'", input.trim())),
+    }
+}
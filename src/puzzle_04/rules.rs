@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+
+/// Something a password's digits must do.  Rules are checked a digit at a time, so whole ranges
+/// can be counted without visiting each number in them.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Rule {
+    /// Each digit is at least as large as the one before it
+    NonDecreasing,
+    /// Some digit appears at least twice in a row
+    HasPair,
+    /// Some run of the same digit is at least this long
+    MinRun(usize),
+    /// Some run of the same digit is exactly this long, e.g. 2 for a pair that isn't part of a
+    /// larger group
+    ExactRun(usize),
+    /// No run of the same digit is longer than this
+    MaxRun(usize),
+    /// The number has exactly this many digits
+    Length(usize),
+}
+
+/// Rules every password must pass, written in code or parsed from statements like
+/// `monotonic nondecreasing; run==2 exists; length 6`
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+    /// Runs longer than this all look the same to the rules
+    run_cap: usize,
+    /// Numbers longer than this all look the same to the rules
+    length_cap: usize,
+}
+
+/// What the rules need to know about the digits so far
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+struct State {
+    /// `None` until the first non-zero digit
+    previous: Option<u8>,
+    /// Length of the run the previous digit is in
+    run: usize,
+    length: usize,
+    /// Bit for each rule that something must exist for, set once it has been seen
+    satisfied: u32,
+}
+
+/// Counts passwords no larger than a limit, remembering how many ways the remaining digits can be
+/// filled in from each state once they're no longer held back by the limit
+struct Counter<'a> {
+    rules: &'a RuleSet,
+    limit: Vec<u8>,
+    memo: HashMap<(usize, State), u64>,
+}
+
+/// Passwords in a range that pass the rules, smallest first, found as they're asked for
+pub struct Candidates<'a> {
+    counter: Counter<'a>,
+    /// The smallest number, padded with zeros to as many digits as the largest
+    min: Vec<u8>,
+    stack: Vec<Frame>,
+}
+
+/// A partly written candidate, and the next digit to try after it
+struct Frame {
+    number: u64,
+    state: State,
+    /// Whether the digits so far match the start of the smallest and largest numbers
+    at_min: bool,
+    at_max: bool,
+    next_digit: u8,
+}
+
+impl RuleSet {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        if rules.len() > 32 {
+            panic!("At most 32 rules can be checked at once, found {}", rules.len());
+        }
+
+        let caps = |size: fn(&Rule) -> Option<usize>| rules.iter().filter_map(size).max().unwrap_or(1) + 1;
+        let run_cap = caps(|rule| match rule {
+            Rule::HasPair => Some(2),
+            Rule::MinRun(x) | Rule::ExactRun(x) | Rule::MaxRun(x) => Some(*x),
+            _ => None,
+        });
+
+        let length_cap = caps(|rule| match rule {
+            Rule::Length(x) => Some(*x),
+            _ => None,
+        });
+
+        RuleSet { rules, run_cap, length_cap }
+    }
+
+    /// Reads rules separated by semicolons or new lines:
+    ///
+    /// * `monotonic nondecreasing` - digits never decrease
+    /// * `run>=N exists` - some run of the same digit is at least N long
+    /// * `run==N exists` - some run of the same digit is exactly N long
+    /// * `run<=N all` - every run of the same digit is at most N long
+    /// * `length N` - the number has exactly N digits
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut rules = Vec::new();
+        for statement in text.split([';', '\n']).map(|x| x.trim()).filter(|x| !x.is_empty()) {
+            rules.push(parse_rule(statement)?);
+        }
+
+        if rules.len() > 32 {
+            return Err(format!("At most 32 rules can be checked at once, found {}", rules.len()));
+        }
+
+        Ok(RuleSet::new(rules))
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Whether the number's digits pass every rule
+    pub fn is_valid(&self, number: u64) -> bool {
+        let mut state = State::start();
+        for digit in digits(number) {
+            state = match state.next(digit, self) {
+                None => return false,
+                Some(x) => x,
+            };
+        }
+
+        state.accepts(self)
+    }
+
+    /// How many numbers from `min` to `max`, inclusive, pass every rule
+    pub fn count(&self, min: u64, max: u64) -> u64 {
+        if min > max {
+            return 0;
+        }
+
+        let below_min = if min == 0 { 0 } else { self.count_up_to(min - 1) };
+        self.count_up_to(max) - below_min
+    }
+
+    /// Numbers from `min` to `max`, inclusive, that pass every rule, in increasing order
+    pub fn candidates(&self, min: u64, max: u64) -> Candidates<'_> {
+        let limit = digits(max);
+        let mut padded = digits(min);
+        while padded.len() < limit.len() {
+            padded.insert(0, 0);
+        }
+
+        let mut stack = Vec::new();
+        if min <= max {
+            stack.push(Frame { number: 0, state: State::start(), at_min: true, at_max: true, next_digit: padded[0] });
+        }
+
+        Candidates { counter: Counter { rules: self, limit, memo: HashMap::new() }, min: padded, stack }
+    }
+
+    fn count_up_to(&self, limit: u64) -> u64 {
+        let mut counter = Counter { rules: self, limit: digits(limit), memo: HashMap::new() };
+        counter.count(0, true, State::start())
+    }
+}
+
+fn parse_rule(statement: &str) -> Result<Rule, String> {
+    let words: Vec<&str> = statement.split_whitespace().collect();
+    let number = |text: &str| text.parse::<usize>()
+        .map_err(|_| format!("Expected a number in '{}', found '{}'", statement, text));
+
+    match words.as_slice() {
+        ["monotonic", "nondecreasing"] => Ok(Rule::NonDecreasing),
+        ["length", x] => Ok(Rule::Length(number(x)?)),
+        [run, quantifier] if run.starts_with("run") => {
+            let condition = &run[3..];
+            let rule = if let Some(x) = condition.strip_prefix(">=") {
+                (Rule::MinRun(number(x)?), "exists")
+            } else if let Some(x) = condition.strip_prefix("==") {
+                (Rule::ExactRun(number(x)?), "exists")
+            } else if let Some(x) = condition.strip_prefix("<=") {
+                (Rule::MaxRun(number(x)?), "all")
+            } else {
+                return Err(format!("Expected run>=N, run==N or run<=N in '{}'", statement));
+            };
+
+            match rule {
+                (_, expected) if expected != *quantifier => {
+                    Err(format!("'{}' needs '{}' after the run length", statement, expected))
+                }
+                (Rule::MinRun(0), _) | (Rule::ExactRun(0), _) => Err(format!("Runs can't be empty in '{}'", statement)),
+                (x, _) => Ok(x),
+            }
+        }
+
+        _ => Err(format!("Unknown rule '{}'", statement)),
+    }
+}
+
+impl State {
+    fn start() -> Self {
+        State { previous: None, run: 0, length: 0, satisfied: 0 }
+    }
+
+    /// The state after another digit, or `None` if no password can start this way
+    fn next(&self, digit: u8, rules: &RuleSet) -> Option<State> {
+        let mut result = *self;
+        match self.previous {
+            None if digit == 0 => return Some(*self),
+            None => result.run = 1,
+            Some(x) if x == digit => result.run = (result.run + 1).min(rules.run_cap),
+            Some(_) => {
+                result = result.end_run(rules);
+                result.run = 1;
+            }
+        }
+
+        result.previous = Some(digit);
+        result.length = (result.length + 1).min(rules.length_cap);
+        for rule in &rules.rules {
+            match rule {
+                Rule::NonDecreasing if self.previous.is_some_and(|x| digit < x) => return None,
+                Rule::MaxRun(x) if result.run > *x => return None,
+                Rule::Length(x) if result.length > *x => return None,
+                _ => (),
+            }
+        }
+
+        Some(result.check_run(rules))
+    }
+
+    /// Marks the rules the current run satisfies as soon as it's long enough
+    fn check_run(mut self, rules: &RuleSet) -> Self {
+        for (index, rule) in rules.rules.iter().enumerate() {
+            let satisfied = match rule {
+                Rule::HasPair => self.run >= 2,
+                Rule::MinRun(x) => self.run >= *x,
+                _ => false,
+            };
+
+            if satisfied {
+                self.satisfied |= 1 << index ;
+            }
+        }
+
+        self
+    }
+
+    /// Marks the rules that depend on how long the run turned out to be
+    fn end_run(mut self, rules: &RuleSet) -> Self {
+        for (index, rule) in rules.rules.iter().enumerate() {
+            if *rule == Rule::ExactRun(self.run) {
+                self.satisfied |= 1 << index ;
+            }
+        }
+
+        self
+    }
+
+    fn accepts(&self, rules: &RuleSet) -> bool {
+        if self.previous.is_none() {
+            return false;
+        }
+
+        let finished = self.end_run(rules);
+        rules.rules.iter().enumerate().all(|(index, rule)| match rule {
+            Rule::HasPair | Rule::MinRun(_) | Rule::ExactRun(_) => finished.satisfied & (1 << index) != 0,
+            Rule::Length(x) => finished.length == *x,
+            _ => true,
+        })
+    }
+}
+
+impl<'a> Counter<'a> {
+    fn count(&mut self, position: usize, limited: bool, state: State) -> u64 {
+        if position == self.limit.len() {
+            return if state.accepts(self.rules) { 1 } else { 0 };
+        }
+
+        if !limited {
+            if let Some(x) = self.memo.get(&(position, state)) {
+                return *x;
+            }
+        }
+
+        let max_digit = if limited { self.limit[position] } else { 9 };
+        let mut total = 0;
+        for digit in 0..=max_digit {
+            if let Some(next) = state.next(digit, self.rules) {
+                total += self.count(position + 1, limited && digit == max_digit, next);
+            }
+        }
+
+        if !limited {
+            self.memo.insert((position, state), total);
+        }
+
+        total
+    }
+}
+
+impl<'a> Iterator for Candidates<'a> {
+    type Item = u64;
+
+    /// Walks the digits depth first, skipping any branch the counter says has no passwords in it
+    fn next(&mut self) -> Option<u64> {
+        let length = self.counter.limit.len();
+        while !self.stack.is_empty() {
+            let position = self.stack.len() - 1;
+            let frame = self.stack.last_mut().unwrap();
+            if position == length {
+                let frame = self.stack.pop().unwrap();
+                if frame.state.accepts(self.counter.rules) {
+                    return Some(frame.number);
+                }
+
+                continue;
+            }
+
+            let max_digit = if frame.at_max { self.counter.limit[position] } else { 9 };
+            if frame.next_digit > max_digit {
+                self.stack.pop();
+                continue;
+            }
+
+            let digit = frame.next_digit;
+            frame.next_digit += 1;
+            let state = match frame.state.next(digit, self.counter.rules) {
+                None => continue,
+                Some(x) => x,
+            };
+
+            let child = Frame {
+                number: frame.number * 10 + digit as u64,
+                state,
+                at_min: frame.at_min && digit == self.min[position],
+                at_max: frame.at_max && digit == max_digit,
+                next_digit: 0,
+            };
+
+            // Branches still held back by either end of the range are cheap enough to just walk
+            if !child.at_min && !child.at_max && self.counter.count(position + 1, false, state) == 0 {
+                continue;
+            }
+
+            let next_digit = if child.at_min && position + 1 < length { self.min[position + 1] } else { 0 };
+            self.stack.push(Frame { next_digit, ..child });
+        }
+
+        None
+    }
+}
+
+fn digits(number: u64) -> Vec<u8> {
+    number.to_string().bytes().map(|x| x - b'0').collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_sets() -> Vec<RuleSet> {
+        vec![
+            RuleSet::new(vec![Rule::NonDecreasing, Rule::HasPair]),
+            RuleSet::new(vec![Rule::NonDecreasing, Rule::ExactRun(2)]),
+            RuleSet::new(vec![Rule::MaxRun(2), Rule::Length(4)]),
+            RuleSet::new(vec![Rule::MinRun(2), Rule::ExactRun(3), Rule::MaxRun(3)]),
+            RuleSet::new(vec![]),
+        ]
+    }
+
+    /// Checks the rules against the lengths of each run of digits, rather than a digit at a time
+    fn check_runs(number: u64, rules: &RuleSet) -> bool {
+        let digits = digits(number);
+        let mut runs = Vec::new();
+        let mut start = 0;
+        for index in 1..=digits.len() {
+            if index == digits.len() || digits[index] != digits[start] {
+                runs.push(index - start);
+                start = index;
+            }
+        }
+
+        number > 0 && rules.rules().iter().all(|rule| match rule {
+            Rule::NonDecreasing => digits.windows(2).all(|x| x[0] <= x[1]),
+            Rule::HasPair => runs.iter().any(|x| *x >= 2),
+            Rule::MinRun(length) => runs.iter().any(|x| x >= length),
+            Rule::ExactRun(length) => runs.contains(length),
+            Rule::MaxRun(length) => runs.iter().all(|x| x <= length),
+            Rule::Length(length) => digits.len() == *length,
+        })
+    }
+
+    #[test]
+    fn checks_single_numbers() {
+        let rule_sets = rule_sets();
+        let (part1, part2) = (&rule_sets[0], &rule_sets[1]);
+        assert!(part1.is_valid(111111));
+        assert!(!part1.is_valid(223450));
+        assert!(!part1.is_valid(123789));
+        assert!(part2.is_valid(112233));
+        assert!(!part2.is_valid(123444));
+        assert!(part2.is_valid(111122));
+        assert!(!rule_sets[2].is_valid(1000));
+        for number in 0..20000 {
+            for rules in &rule_sets {
+                assert_eq!(rules.is_valid(number), check_runs(number, rules), "{} with {:?}", number, rules);
+            }
+        }
+    }
+
+    #[test]
+    fn counts_and_lists_match_checking_each_number() {
+        for rules in &rule_sets() {
+            for (min, max) in &[(0, 5000), (1, 1), (98, 12345), (70000, 70999)] {
+                let expected: Vec<u64> = (*min..=*max).filter(|x| check_runs(*x, rules)).collect();
+                assert_eq!(rules.count(*min, *max), expected.len() as u64, "{:?} from {} to {}", rules, min, max);
+                assert_eq!(rules.candidates(*min, *max).collect::<Vec<u64>>(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn counts_large_ranges() {
+        // Non-decreasing 18 digit numbers are the ways to pick 18 digits from 1-9 with repeats
+        let rules = RuleSet::new(vec![Rule::NonDecreasing]);
+        assert_eq!(rules.count(100_000_000_000_000_000, 999_999_999_999_999_999), 1_562_275);
+        assert_eq!(rules.candidates(100_000_000_000_000_000, u64::MAX).nth(2), Some(111_111_111_111_111_113));
+        assert!(RuleSet::new(vec![Rule::NonDecreasing, Rule::ExactRun(4)]).count(0, u64::MAX) > 0);
+        assert_eq!(rules.count(10, 5), 0);
+        assert_eq!(rules.candidates(10, 5).next(), None);
+    }
+
+    #[test]
+    fn parses_rules() {
+        let rules = RuleSet::parse("monotonic nondecreasing; run==2 exists; length 6").unwrap();
+        assert_eq!(rules.rules(), &[Rule::NonDecreasing, Rule::ExactRun(2), Rule::Length(6)]);
+
+        let rules = RuleSet::parse("run>=3 exists\nrun<=4 all;;").unwrap();
+        assert_eq!(rules.rules(), &[Rule::MinRun(3), Rule::MaxRun(4)]);
+        assert_eq!(rules.candidates(1, 99999).take(3).collect::<Vec<u64>>(), vec![111, 222, 333]);
+
+        assert_eq!(RuleSet::parse("length six"), Err("Expected a number in 'length six', found 'six'".to_owned()));
+        assert!(RuleSet::parse("run<=2 exists").unwrap_err().contains("needs 'all'"));
+        assert!(RuleSet::parse("run!=2 exists").is_err());
+        assert!(RuleSet::parse("run==0 exists").is_err());
+        assert!(RuleSet::parse("monotonic increasing").is_err());
+    }
+}
@@ -1,12 +1,37 @@
-use crate::intcode::Machine;
+use crate::intcode::{parse_program, Machine};
+use crate::puzzle::Puzzle;
 
-pub fn run() {
-    let mut machine = Machine::new_from_file("src/inputs/05A.txt");
-    machine.input_buffer.push_front(5);
+pub struct Day05;
+
+impl Puzzle for Day05 {
+    type Input = Vec<i128>;
+    type Part1 = i128;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Vec<i128>) -> i128 {
+        run_diagnostics(program, 1)
+    }
+
+    fn part2(program: &Vec<i128>) -> i128 {
+        run_diagnostics(program, 5)
+    }
+}
+
+/// Runs the diagnostic program for the system id, returning the final diagnostic code.  Every
+/// test output before it must be 0.
+fn run_diagnostics(program: &[i128], system_id: i128) -> i128 {
+    let mut machine = Machine::new_from_memory(program.to_vec());
+    machine.input_buffer.push_front(system_id);
     machine.run_program();
 
-    println!("Output:");
-    for output in machine.output_buffer.drain(..) {
-        println!("{}", output);
+    let code = machine.output_buffer.pop_back().unwrap();
+    if let Some(failed) = machine.output_buffer.iter().find(|x| **x != 0) {
+        panic!("Diagnostic test failed with output {}", failed);
     }
-}
\ No newline at end of file
+
+    code
+}
@@ -1,105 +1,39 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::Read;
+use crate::puzzle::Puzzle;
+use self::orbits::OrbitTree;
 
-pub fn run() {
-    let input = read_input();
-    let objects = build_object_map(input);
+pub mod orbits;
 
-    let count: i32 = objects.names.iter()
-        .map(|x| get_orbit_counts(x, 0, &objects.orbit_info))
-        .sum();
+pub struct Day06;
 
-    println!("Total orbits {}", count);
+impl Puzzle for Day06 {
+    type Input = OrbitTree;
+    type Part1 = usize;
+    type Part2 = usize;
 
-    let transfers = get_transfer_count(&objects.orbit_info);
-    println!("Transfers: {}", transfers);
-}
-
-struct Object {
-    pub id: String,
-    pub orbits: Option<String>,
-}
-
-struct Objects {
-    pub names: HashSet<String>,
-    pub orbit_info: HashMap<String, Object>,
-}
-
-fn build_object_map(input: String) -> Objects {
-    let mut map = HashMap::new();
-    let mut set = HashSet::new();
-
-    for line in input.lines() {
-        let parts: Vec<&str> = line.split(')').collect();
-        if parts.len() != 2 {
-            panic!("Incorrect number of parts for object: {}", parts.len());
-        }
-
-        let inner = parts[0].to_owned();
-        let outer = parts[1].to_owned();
-
-        if !map.contains_key(&inner) {
-            map.insert(inner.clone(), Object { id: inner.clone(), orbits: None });
-        }
-
-        map.insert(outer.clone(), Object { id: outer.clone(), orbits: Some(inner.clone())});
-        set.insert(inner.clone());
-        set.insert(outer.clone());
-    }
-
-    Objects { names: set, orbit_info: map }
-}
-
-fn get_orbit_counts(obj_name: &String, depth: i32, map: &HashMap<String, Object>) -> i32 {
-    match map.get(obj_name) {
-        None => panic!("Obj {} wasn't found in the map"),
-        Some(obj) => {
-            match &obj.orbits {
-                None => depth,
-                Some(inner) => get_orbit_counts(inner, depth + 1, map)
+    fn parse(input: &str) -> OrbitTree {
+        match OrbitTree::parse(input) {
+            Ok(x) => x,
+            Err(errors) => {
+                let messages: Vec<String> = errors.iter().map(|x| x.to_string()).collect();
+                panic!("Invalid orbit map:\n{}", messages.join("\n"))
             }
         }
     }
-}
 
-fn get_orbit_transfers(obj_name: &String, map: &HashMap<String, Object>, mut list: Vec<String>) -> Vec<String> {
-    match map.get(obj_name) {
-        None => panic!("Obj {} wasn't found in the map"),
-        Some(obj) => {
-            match &obj.orbits {
-                None => list,
-                Some(inner) => {
-                    list.push(inner.clone());
-                    get_orbit_transfers(inner, map, list)
-                }
-            }
-        }
+    fn part1(tree: &OrbitTree) -> usize {
+        tree.total_orbits()
     }
-}
-
-fn get_transfer_count(map: &HashMap<String, Object>) -> i32 {
-    let you_transfers = get_orbit_transfers(&"YOU".to_owned(), map, Vec::new());
-    let san_transfers = get_orbit_transfers(&"SAN".to_owned(), map, Vec::new());
 
-    println!("length {}", you_transfers.len());
+    /// Orbital transfers between the objects YOU and SAN are orbiting
+    fn part2(tree: &OrbitTree) -> usize {
+        let find = |name| match tree.id(name) {
+            None => panic!("{} isn't in the orbit map", name),
+            Some(x) => x,
+        };
 
-    for x in 0..san_transfers.len() {
-        for y in 0..you_transfers.len() {
-            println!("{} {}", san_transfers[x], you_transfers[y]);
-            if san_transfers[x] == you_transfers[y] {
-                return (x + y) as i32;
-            }
+        match tree.transfers(find("YOU"), find("SAN")) {
+            None => panic!("YOU and SAN need to be orbiting something"),
+            Some(x) => x,
         }
     }
-
-    panic!("No common objects found!");
 }
-
-fn read_input() -> String {
-    let mut file = File::open("src/inputs/06A.txt").unwrap();
-    let mut content = String::new();
-    file.read_to_string(&mut content).unwrap();
-
-    content
-}
\ No newline at end of file
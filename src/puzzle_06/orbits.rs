@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::fmt;
+
+/// Index of a body in an `OrbitTree`
+pub type BodyId = usize;
+
+/// Which body each body orbits, with names interned to ids so queries don't need to hash strings
+#[derive(Debug, Clone)]
+pub struct OrbitTree {
+    names: Vec<String>,
+    ids: HashMap<String, BodyId>,
+    parents: Vec<Option<BodyId>>,
+    depths: Vec<usize>,
+}
+
+/// A problem with an orbit map, with line numbers counting from 1
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum OrbitError {
+    Empty,
+    Malformed { line: usize, text: String },
+    /// A body declared as orbiting a second time, which is ignored in favor of the first
+    DuplicateParent { line: usize, body: String, parent: String, first_line: usize, first_parent: String },
+    /// Bodies that orbit each other in a loop, in the order they orbit, with the line each orbit
+    /// was declared on
+    Cycle { bodies: Vec<String>, lines: Vec<usize> },
+    /// Every body that doesn't orbit anything, with the line it first appears on
+    MultipleRoots { roots: Vec<(String, usize)> },
+    /// A body whose orbits don't lead back to the root, or to any root if they end in a cycle
+    Unreachable { line: usize, body: String, root: Option<String> },
+}
+
+/// Where each body's orbit is for walking up the tree before it has been validated
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+enum Ending {
+    Root(BodyId),
+    Cycle,
+}
+
+impl fmt::Display for OrbitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrbitError::Empty => write!(f, "The orbit map is empty"),
+            OrbitError::Malformed { line, text } => {
+                write!(f, "Line {}: expected an orbit in the form A)B, found '{}'", line, text)
+            }
+
+            OrbitError::DuplicateParent { line, body, parent, first_line, first_parent } => {
+                write!(f, "Line {}: {} can't orbit {}, it already orbits {} (line {})",
+                       line, body, parent, first_parent, first_line)
+            }
+
+            OrbitError::Cycle { bodies, lines } => {
+                let lines: Vec<String> = lines.iter().map(|x| x.to_string()).collect();
+                write!(f, "Lines {}: {} orbit each other in a cycle", lines.join(", "), bodies.join(", "))
+            }
+
+            OrbitError::MultipleRoots { roots } => {
+                let roots: Vec<String> = roots.iter().map(|(name, line)| format!("{} (line {})", name, line)).collect();
+                write!(f, "Expected one body that doesn't orbit anything, found {}: {}", roots.len(), roots.join(", "))
+            }
+
+            OrbitError::Unreachable { line, body, root: Some(root) } => {
+                write!(f, "Line {}: {} isn't connected to {}", line, body, root)
+            }
+
+            OrbitError::Unreachable { line, body, root: None } => {
+                write!(f, "Line {}: {} isn't connected to any body that doesn't orbit anything", line, body)
+            }
+        }
+    }
+}
+
+impl OrbitTree {
+    /// Reads lines of `A)B`, meaning B orbits A.  The orbits must form a single tree, and every
+    /// problem with them is returned rather than just the first.
+    pub fn parse(input: &str) -> Result<Self, Vec<OrbitError>> {
+        let mut tree = OrbitTree { names: Vec::new(), ids: HashMap::new(), parents: Vec::new(), depths: Vec::new() };
+        let mut errors = Vec::new();
+        let mut first_seen = Vec::new();
+        let mut declared_on: Vec<Option<usize>> = Vec::new();
+
+        for (index, text) in input.lines().enumerate() {
+            let line = index + 1;
+            let text = text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let parts: Vec<&str> = text.split(')').map(|x| x.trim()).collect();
+            if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
+                errors.push(OrbitError::Malformed { line, text: text.to_owned() });
+                continue;
+            }
+
+            // A second parent is rejected before its name is interned, so a body only named on
+            // the rejected line isn't reported again as another root
+            let first = tree.id(parts[1]).and_then(|x| declared_on[x].map(|line| (x, line)));
+            if let Some((outer, first_line)) = first {
+                errors.push(OrbitError::DuplicateParent {
+                    line,
+                    body: tree.names[outer].clone(),
+                    parent: parts[0].to_owned(),
+                    first_line,
+                    first_parent: tree.names[tree.parents[outer].unwrap()].clone(),
+                });
+
+                continue;
+            }
+
+            let mut bodies = Vec::new();
+            for name in &parts {
+                let id = tree.intern(name);
+                if id == first_seen.len() {
+                    first_seen.push(line);
+                    declared_on.push(None);
+                }
+
+                bodies.push(id);
+            }
+
+            let (inner, outer) = (bodies[0], bodies[1]);
+            tree.parents[outer] = Some(inner);
+            declared_on[outer] = Some(line);
+        }
+
+        if tree.names.is_empty() && errors.is_empty() {
+            errors.push(OrbitError::Empty);
+        }
+
+        errors.extend(tree.check_connections(&first_seen, &declared_on));
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        tree.depths = tree.calculate_depths();
+        Ok(tree)
+    }
+
+    /// Finds cycles, extra roots and the bodies that can't reach the root because of them
+    fn check_connections(&self, first_seen: &[usize], declared_on: &[Option<usize>]) -> Vec<OrbitError> {
+        let mut errors = Vec::new();
+        let mut endings: Vec<Option<Ending>> = vec![None; self.names.len()];
+        let mut in_cycle = vec![false; self.names.len()];
+
+        for start in 0..self.names.len() {
+            // Walks up until reaching a root, a body already walked from, or a body on this walk
+            let mut walk: Vec<BodyId> = Vec::new();
+            let mut on_walk: HashMap<BodyId, usize> = HashMap::new();
+            let mut current = start;
+            let ending = loop {
+                if let Some(x) = endings[current] {
+                    break x;
+                }
+
+                if let Some(position) = on_walk.get(&current) {
+                    let cycle = &walk[*position..];
+                    for id in cycle {
+                        in_cycle[*id] = true;
+                    }
+
+                    errors.push(OrbitError::Cycle {
+                        bodies: cycle.iter().map(|x| self.names[*x].clone()).collect(),
+                        lines: cycle.iter().map(|x| declared_on[*x].unwrap()).collect(),
+                    });
+
+                    break Ending::Cycle;
+                }
+
+                on_walk.insert(current, walk.len());
+                walk.push(current);
+                match self.parents[current] {
+                    None => break Ending::Root(current),
+                    Some(x) => current = x,
+                }
+            };
+
+            for id in walk {
+                endings[id] = Some(ending);
+            }
+        }
+
+        let roots: Vec<BodyId> = (0..self.names.len()).filter(|x| self.parents[*x].is_none()).collect();
+        let main_root = match roots.as_slice() {
+            [x] => Some(*x),
+            _ => self.id("COM").filter(|x| self.parents[*x].is_none()),
+        };
+
+        if roots.len() > 1 {
+            errors.push(OrbitError::MultipleRoots {
+                roots: roots.iter().map(|x| (self.names[*x].clone(), first_seen[*x])).collect(),
+            });
+        }
+
+        let mut unreachable = Vec::new();
+        for id in 0..self.names.len() {
+            let connected = match endings[id].unwrap() {
+                Ending::Cycle => false,
+                Ending::Root(x) => main_root.is_none() || main_root == Some(x),
+            };
+
+            if !connected && !in_cycle[id] && self.parents[id].is_some() {
+                unreachable.push(OrbitError::Unreachable {
+                    line: declared_on[id].unwrap(),
+                    body: self.names[id].clone(),
+                    root: main_root.map(|x| self.names[x].clone()),
+                });
+            }
+        }
+
+        unreachable.sort_by_key(|x| match x {
+            OrbitError::Unreachable { line, .. } => *line,
+            _ => 0,
+        });
+
+        errors.extend(unreachable);
+        errors
+    }
+
+    fn intern(&mut self, name: &str) -> BodyId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+
+        let id = self.names.len();
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        self.parents.push(None);
+        id
+    }
+
+    /// Walks up from each body until it reaches one whose depth is known, then fills in the depths
+    /// on the way back down, so long chains don't need deep recursion.  The orbits must already be
+    /// known to form a tree.
+    fn calculate_depths(&self) -> Vec<usize> {
+        let mut depths: Vec<Option<usize>> = vec![None; self.names.len()];
+        for start in 0..self.names.len() {
+            let mut chain = Vec::new();
+            let mut current = Some(start);
+            let mut depth = 0;
+            while let Some(id) = current {
+                if let Some(x) = depths[id] {
+                    depth = x + 1;
+                    break;
+                }
+
+                chain.push(id);
+                current = self.parents[id];
+            }
+
+            for id in chain.into_iter().rev() {
+                depths[id] = Some(depth);
+                depth += 1;
+            }
+        }
+
+        depths.into_iter().map(|x| x.unwrap()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn id(&self, name: &str) -> Option<BodyId> {
+        self.ids.get(name).cloned()
+    }
+
+    pub fn name(&self, id: BodyId) -> &str {
+        &self.names[id]
+    }
+
+    /// The body this one orbits, if any
+    pub fn parent(&self, id: BodyId) -> Option<BodyId> {
+        self.parents[id]
+    }
+
+    /// How many bodies this one orbits, directly or indirectly
+    pub fn depth(&self, id: BodyId) -> usize {
+        self.depths[id]
+    }
+
+    /// Direct and indirect orbits of every body
+    pub fn total_orbits(&self) -> usize {
+        self.depths.iter().sum()
+    }
+
+    /// The bodies this one orbits, closest first
+    pub fn ancestors(&self, id: BodyId) -> impl Iterator<Item = BodyId> + '_ {
+        std::iter::successors(self.parents[id], move |x| self.parents[*x])
+    }
+
+    /// The deepest body both bodies are in orbit of or are, or `None` if they aren't connected
+    pub fn lowest_common_ancestor(&self, first: BodyId, second: BodyId) -> Option<BodyId> {
+        let (mut first, mut second) = (first, second);
+        while self.depths[first] > self.depths[second] {
+            first = self.parents[first].unwrap();
+        }
+
+        while self.depths[second] > self.depths[first] {
+            second = self.parents[second].unwrap();
+        }
+
+        while first != second {
+            first = self.parents[first]?;
+            second = self.parents[second]?;
+        }
+
+        Some(first)
+    }
+
+    /// The bodies from one to the other, both included, going through their lowest common
+    /// ancestor
+    pub fn path(&self, from: BodyId, to: BodyId) -> Option<Vec<BodyId>> {
+        let common = self.lowest_common_ancestor(from, to)?;
+        let mut result: Vec<BodyId> = std::iter::once(from).chain(self.ancestors(from))
+            .take(self.depths[from] - self.depths[common] + 1)
+            .collect();
+
+        let mut rest: Vec<BodyId> = std::iter::once(to).chain(self.ancestors(to))
+            .take(self.depths[to] - self.depths[common])
+            .collect();
+
+        rest.reverse();
+        result.extend(rest);
+        Some(result)
+    }
+
+    /// Steps between two bodies through the tree
+    pub fn distance(&self, from: BodyId, to: BodyId) -> Option<usize> {
+        let common = self.lowest_common_ancestor(from, to)?;
+        Some(self.depths[from] + self.depths[to] - 2 * self.depths[common])
+    }
+
+    /// Orbital transfers needed to move from the body `from` orbits to the one `to` orbits
+    pub fn transfers(&self, from: BodyId, to: BodyId) -> Option<usize> {
+        self.distance(self.parents[from]?, self.parents[to]?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\nK)YOU\nI)SAN";
+
+    fn names(tree: &OrbitTree, ids: &[BodyId]) -> Vec<String> {
+        ids.iter().map(|x| tree.name(*x).to_owned()).collect()
+    }
+
+    #[test]
+    fn counts_orbits() {
+        let tree = OrbitTree::parse(EXAMPLE).unwrap();
+        assert_eq!(tree.len(), 14);
+        assert_eq!(tree.total_orbits(), 42 + 7 + 5);
+        assert_eq!(tree.depth(tree.id("L").unwrap()), 7);
+        assert_eq!(names(&tree, &tree.ancestors(tree.id("D").unwrap()).collect::<Vec<BodyId>>()), vec!["C", "B", "COM"]);
+    }
+
+    #[test]
+    fn finds_paths() {
+        let tree = OrbitTree::parse(EXAMPLE).unwrap();
+        let id = |name| tree.id(name).unwrap();
+        assert_eq!(tree.lowest_common_ancestor(id("YOU"), id("SAN")), Some(id("D")));
+        assert_eq!(tree.lowest_common_ancestor(id("H"), id("B")), Some(id("B")));
+        assert_eq!(tree.transfers(id("YOU"), id("SAN")), Some(4));
+        assert_eq!(names(&tree, &tree.path(id("L"), id("I")).unwrap()), vec!["L", "K", "J", "E", "D", "I"]);
+        assert_eq!(names(&tree, &tree.path(id("C"), id("C")).unwrap()), vec!["C"]);
+        assert_eq!(tree.transfers(id("COM"), id("SAN")), None);
+    }
+
+    #[test]
+    fn handles_deep_chains() {
+        let chain: Vec<String> = (0..100_000).map(|x| format!("{}){}", x, x + 1)).collect();
+        let tree = OrbitTree::parse(&chain.join("\n")).unwrap();
+        assert_eq!(tree.depth(tree.id("100000").unwrap()), 100_000);
+        assert_eq!(tree.distance(tree.id("10").unwrap(), tree.id("99990").unwrap()), Some(99_980));
+    }
+
+    #[test]
+    fn reports_problems_with_line_numbers() {
+        let errors = OrbitTree::parse("COM)B\nB)C\nA)C\n\nnonsense\nD)E\nE)F\nF)D\nF)G\nX)Y").unwrap_err();
+        let messages: Vec<String> = errors.iter().map(|x| x.to_string()).collect();
+        assert_eq!(messages, vec![
+            "Line 3: C can't orbit A, it already orbits B (line 2)",
+            "Line 5: expected an orbit in the form A)B, found 'nonsense'",
+            "Lines 8, 7, 6: D, F, E orbit each other in a cycle",
+            "Expected one body that doesn't orbit anything, found 2: COM (line 1), X (line 10)",
+            "Line 9: G isn't connected to COM",
+            "Line 10: Y isn't connected to COM",
+        ]);
+    }
+
+    #[test]
+    fn reports_cycles_without_a_root() {
+        assert_eq!(OrbitTree::parse("A)A").unwrap_err(), vec![OrbitError::Cycle { bodies: vec!["A".to_owned()], lines: vec![1] }]);
+        assert_eq!(OrbitTree::parse("\n").unwrap_err(), vec![OrbitError::Empty]);
+
+        let errors = OrbitTree::parse("A)B\nB)A\nB)C").unwrap_err();
+        assert_eq!(errors, vec![
+            OrbitError::Cycle { bodies: vec!["A".to_owned(), "B".to_owned()], lines: vec![2, 1] },
+            OrbitError::Unreachable { line: 3, body: "C".to_owned(), root: None },
+        ]);
+
+        assert!(errors[1].to_string().ends_with("isn't connected to any body that doesn't orbit anything"));
+    }
+}
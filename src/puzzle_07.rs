@@ -1,23 +1,41 @@
-use std::fs::File;
-use std::io::{Read};
-use crate::intcode::{Machine, MachineState};
+use crate::intcode::{parse_program, Machine, MachineState};
+use crate::puzzle::Puzzle;
 use std::collections::VecDeque;
 
-pub fn run() {
-    let program = read_initial_memory();
-    let mut permutations = get_permutations(&vec![5, 6, 7,8, 9], 0);
+pub struct Day07;
+
+impl Puzzle for Day07 {
+    type Input = Vec<i128>;
+    type Part1 = i128;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Vec<i128>) -> i128 {
+        get_max_signal(program, vec![0, 1, 2, 3, 4])
+    }
+
+    fn part2(program: &Vec<i128>) -> i128 {
+        get_max_signal(program, vec![5, 6, 7, 8, 9])
+    }
+}
+
+fn get_max_signal(program: &[i128], phases: Vec<i128>) -> i128 {
+    let mut permutations = get_permutations(&phases, 0);
     permutations.sort();
     permutations.dedup();
 
     let mut max_signal = 0;
     for settings in permutations {
-        let signal = run_amps(program.clone(), settings);
+        let signal = run_amps(program.to_vec(), settings);
         if signal > max_signal {
             max_signal = signal;
         }
     }
 
-    println!("Final signal: {}", max_signal);
+    max_signal
 }
 
 fn run_amps(program: Vec<i128>, phase_settings: Vec<i128>) -> i128 {
@@ -26,7 +44,7 @@ fn run_amps(program: Vec<i128>, phase_settings: Vec<i128>) -> i128 {
 
     let mut amps: Vec<Machine> = (0..phase_settings.len())
         .map(|x| {
-            let mut machine = Machine::new_from_memory(program.clone());
+            let mut machine = Machine::new_from_memory(program.to_vec());
             machine.input_buffer.push_front(phase_settings[x]);
             machine
         })
@@ -69,21 +87,7 @@ fn run_amps(program: Vec<i128>, phase_settings: Vec<i128>) -> i128 {
     signal
 }
 
-fn read_initial_memory() -> Vec<i128> {
-    let mut file = File::open("src/inputs/07A.txt").unwrap();
-    let mut content = String::new();
-    file.read_to_string(&mut content).unwrap();
-
-    let mut output = Vec::new();
-    for code in content.trim().split(",") {
-        let code_as_int = code.parse::<i128>().unwrap();
-        output.push(code_as_int);
-    }
-
-    output
-}
-
-fn get_permutations(initial: &Vec<i128>, item_index: usize) -> Vec<Vec<i128>> {
+fn get_permutations(initial: &[i128], item_index: usize) -> Vec<Vec<i128>> {
     let mut results = Vec::new();
 
     if item_index < initial.len() {
@@ -92,7 +96,7 @@ fn get_permutations(initial: &Vec<i128>, item_index: usize) -> Vec<Vec<i128>> {
                 continue;
             }
 
-            let mut swapped = initial.clone();
+            let mut swapped = initial.to_vec();
             swapped.swap(swap_index, item_index);
 
             let mut recursive_results = get_permutations(&swapped, item_index + 1);
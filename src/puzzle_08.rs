@@ -1,105 +1,76 @@
-use std::fs::{File};
-use std::io::Read;
+use crate::geometry::Grid;
+use crate::image::{self, Image};
+use crate::ocr;
+use crate::puzzle::Puzzle;
+use self::sif::{Pixel, SifImage, BLACK, TRANSPARENT, WHITE};
+
+pub mod convert;
+pub mod sif;
 
 const WIDTH: usize = 25;
 const HEIGHT: usize = 6;
 
-pub fn run() {
-    let input = read_input();
-    let layers = split_into_layers(input, WIDTH, HEIGHT);
+pub struct Day08;
 
-    calc_part_1(&layers);
+impl Puzzle for Day08 {
+    type Input = SifImage;
+    type Part1 = usize;
+    type Part2 = String;
 
-    for layer in &layers {
-        println!("{:?}", layer);
+    fn parse(input: &str) -> SifImage {
+        SifImage::decode(input, WIDTH, HEIGHT).unwrap_or_else(|message| panic!("{}", message))
     }
 
-    let mut resulting_image = Vec::new();
-    for x in 0..(WIDTH * HEIGHT) {
-        let mut pixel = 0;
-        for y in 0..layers.len() {
-            if layers[y][x] != 2 {
-                // White or black
-                pixel = layers[y][x];
-                break;
-            }
-        }
+    /// Number of 1 digits multiplied by the number of 2 digits on the layer with the fewest 0s
+    fn part1(image: &SifImage) -> usize {
+        let counts = image.digit_counts().into_iter()
+            .min_by_key(|x| x.count(BLACK))
+            .unwrap();
 
-        resulting_image.push(pixel);
+        counts.count(WHITE) * counts.count(TRANSPARENT)
     }
 
-    println!();
-    println!("Final image:");
-    println!("{:?}", resulting_image);
-    render_layer(&resulting_image);
+    fn part2(image: &SifImage) -> String {
+        let message = flatten(image);
+        ocr::recognize(&message, |pixel| *pixel == Pixel::White).unwrap_or_else(|message| panic!("{}", message))
+    }
 }
 
-fn read_input() -> Vec<i32> {
-    let mut file = File::open("src/inputs/08a.txt").unwrap();
-    let mut content = String::new();
-    file.read_to_string(&mut content).unwrap();
-
-    content.trim().chars()
-        .map(|ch| ch.to_string().parse::<i32>().unwrap())
-        .collect::<Vec<i32>>()
+/// The decoded message, as white text on black
+pub fn render_image(input: &str) -> Image {
+    let message = flatten(&Day08::parse(input));
+    Image::from_grid(&message, |pixel| if *pixel == Pixel::White { image::WHITE } else { image::BLACK })
 }
 
-fn split_into_layers(mut input: Vec<i32>, width: usize, height: usize) -> Vec<Vec<i32>> {
-    let mut results = Vec::new();
-
-    loop {
-        if input.len() == width * height {
-            break;
-        }
-
-        let remaining = input.split_off(width * height);
-        results.push(input.clone());
-
-        input = remaining;
-    }
-
-    results.push(input);
-    results
+fn flatten(image: &SifImage) -> Grid<Pixel> {
+    image.flatten().unwrap_or_else(|message| panic!("{}", message))
 }
 
-fn render_layer(layer: &Vec<i32>) {
-    for x in 0..layer.len() {
-        if x % WIDTH == 0 {
-            println!();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draws the text with the small OCR letters, one letter to every 5 columns
+    fn draw_message(text: &str) -> Grid<bool> {
+        let mut rows = vec![String::new(); HEIGHT];
+        for letter in text.chars() {
+            let (_, glyph) = ocr::SMALL_GLYPHS.iter().find(|(x, _)| *x == letter).unwrap();
+            for (row, pattern) in rows.iter_mut().zip(glyph.iter()) {
+                row.push_str(&format!("{:.<5}", pattern));
+            }
         }
 
-        if layer[x] == 0 {
-            print!(" ")
-        } else {
-            print!("█")
-        }
+        Grid::parse(&rows.join("\n"), |ch| ch == '#')
     }
 
-    println!();
-    println!();
-}
-
-fn calc_part_1(layers: &Vec<Vec<i32>>) {
-    let mut min_zeros = 5000;
-    let mut answer = 0;
-    for x in 0..layers.len() {
-        let mut zero_count = 0;
-        let mut one_count = 0;
-        let mut two_count = 0;
+    #[test]
+    fn reads_a_split_message() {
+        let message = draw_message("HOUSE");
+        assert_eq!((message.width(), message.height()), (WIDTH, HEIGHT));
 
-        for digit in &layers[x] {
-            if *digit == 0 { zero_count = zero_count + 1 }
-            if *digit == 1 { one_count = one_count + 1 }
-            if *digit == 2 { two_count = two_count + 1 }
+        for seed in 0..5 {
+            let encoded = convert::split_into_layers(&message, 20, seed).encode();
+            assert_eq!(Day08::part2(&Day08::parse(&encoded)), "HOUSE");
         }
-
-        if zero_count < min_zeros {
-            min_zeros = zero_count;
-            answer = one_count * two_count;
-        }
-
-        //println!("Layer {}: {} {} {}", x + 1, zero_count, one_count, two_count);
     }
-
-    println!("Part 1 answer: {}", answer);
-}
\ No newline at end of file
+}
@@ -0,0 +1,119 @@
+use crate::display::Rgb;
+use crate::geometry::Grid;
+use crate::image::{self, Image};
+use super::sif::{Pixel, SifImage, BLACK, TRANSPARENT, WHITE};
+
+/// Drawn where no layer covers a pixel, dark enough to stay unset when read back as a bitmap
+pub const TRANSPARENT_COLOR: Rgb = (96, 96, 96);
+
+/// A xorshift generator, so the same seed always splits an image the same way
+struct Random(u64);
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        // The state must never be zero
+        Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn below(&mut self, limit: usize) -> usize {
+        self.0 = self.0 ^ (self.0 << 13);
+        self.0 = self.0 ^ (self.0 >> 7);
+        self.0 = self.0 ^ (self.0 << 17);
+        (self.0 % limit as u64) as usize
+    }
+}
+
+/// Spreads a bitmap over `layer_count` layers.  Each pixel's color goes on a random layer, with
+/// transparent pixels in front of it and random digits behind it, so flattening the result gives
+/// back the bitmap.
+pub fn split_into_layers(bitmap: &Grid<bool>, layer_count: usize, seed: u64) -> SifImage {
+    if layer_count == 0 {
+        panic!("An image needs at least one layer");
+    }
+
+    let mut random = Random::new(seed);
+    let mut layers = vec![Vec::with_capacity(bitmap.width() * bitmap.height()); layer_count];
+    for (_, lit) in bitmap.iter() {
+        let color = if *lit { WHITE } else { BLACK };
+        let visible_layer = random.below(layer_count);
+        for (index, layer) in layers.iter_mut().enumerate() {
+            let digit = match index {
+                x if x < visible_layer => TRANSPARENT,
+                x if x == visible_layer => color,
+                _ => [BLACK, WHITE, TRANSPARENT][random.below(3)],
+            };
+
+            layer.push(digit);
+        }
+    }
+
+    SifImage::from_layers(bitmap.width(), bitmap.height(), layers).unwrap()
+}
+
+/// The flattened image, with white and black pixels kept and uncovered pixels drawn gray
+pub fn to_image(sif: &SifImage) -> Result<Image, String> {
+    let flat = sif.flatten()?;
+    Ok(Image::from_grid(&flat, |pixel| match pixel {
+        Pixel::Black => image::BLACK,
+        Pixel::White => image::WHITE,
+        Pixel::Transparent => TRANSPARENT_COLOR,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMILE: &str = "\
+.......
+.#...#.
+.......
+#.....#
+.#####.";
+
+    fn smile() -> Grid<bool> {
+        Grid::parse(SMILE, |ch| ch == '#')
+    }
+
+    fn render(grid: &Grid<Pixel>) -> String {
+        grid.render(|x| if *x == Pixel::White { '#' } else { '.' })
+    }
+
+    #[test]
+    fn layers_flatten_back_to_the_bitmap() {
+        for seed in 0..20 {
+            let sif = split_into_layers(&smile(), 5, seed);
+            assert_eq!(sif.layers().len(), 5);
+            assert_eq!(render(&sif.flatten().unwrap()), SMILE);
+
+            let decoded = SifImage::decode(&sif.encode(), sif.width(), sif.height()).unwrap();
+            assert_eq!(decoded, sif);
+        }
+
+        assert_eq!(split_into_layers(&smile(), 1, 3), SifImage::from_bitmap(&smile()));
+    }
+
+    #[test]
+    fn seeds_pick_the_layers() {
+        let first = split_into_layers(&smile(), 4, 7);
+        assert_eq!(first, split_into_layers(&smile(), 4, 7));
+        assert_ne!(first, split_into_layers(&smile(), 4, 8));
+
+        // Some pixels need to show through from behind the front layer
+        assert!(first.digit_counts()[0].count(TRANSPARENT) > 0);
+    }
+
+    #[test]
+    fn round_trips_through_png() {
+        let sif = split_into_layers(&smile(), 3, 42);
+        let png = to_image(&sif).unwrap().to_png(1);
+        let bitmap = Image::decode(&png).unwrap().to_bitmap();
+        assert_eq!(split_into_layers(&bitmap, 3, 42), sif);
+
+        let uncovered = SifImage::decode("21", 2, 1).unwrap();
+        let image = to_image(&uncovered).unwrap();
+        assert_eq!(image.get(0, 0), TRANSPARENT_COLOR);
+        let bitmap = Image::decode(&image.to_png(1)).unwrap().to_bitmap();
+        assert_eq!(bitmap.render(|x| if *x { '#' } else { '.' }), ".#");
+    }
+}
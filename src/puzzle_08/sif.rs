@@ -0,0 +1,188 @@
+use crate::geometry::{Grid, Point};
+
+pub const BLACK: u8 = 0;
+pub const WHITE: u8 = 1;
+pub const TRANSPARENT: u8 = 2;
+
+/// What a pixel looks like once every layer has been stacked
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum Pixel { Black, White, Transparent }
+
+/// How many times each digit appears in a layer
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct DigitCounts([usize; 10]);
+
+/// An image in the Space Image Format: layers of `width * height` digits, row by row, with
+/// the first layer in front
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SifImage {
+    width: usize,
+    height: usize,
+    layers: Vec<Vec<u8>>,
+}
+
+impl DigitCounts {
+    pub fn count(&self, digit: u8) -> usize {
+        self.0[digit as usize]
+    }
+}
+
+impl SifImage {
+    /// Splits the digits into layers, ignoring surrounding whitespace
+    pub fn decode(data: &str, width: usize, height: usize) -> Result<Self, String> {
+        let layer_size = check_size(width, height)?;
+        let mut digits = Vec::new();
+        for (index, ch) in data.trim().chars().enumerate() {
+            match ch.to_digit(10) {
+                None => return Err(format!("Expected a digit at position {}, found '{}'", index + 1, ch)),
+                Some(x) => digits.push(x as u8),
+            }
+        }
+
+        if digits.is_empty() || !digits.len().is_multiple_of(layer_size) {
+            return Err(format!("{} digits can't be split into {}x{} layers of {} digits",
+                               digits.len(), width, height, layer_size));
+        }
+
+        let layers = digits.chunks(layer_size).map(|x| x.to_vec()).collect();
+        Ok(SifImage { width, height, layers })
+    }
+
+    /// Builds an image from layers that are already split, each holding `width * height` digits
+    pub fn from_layers(width: usize, height: usize, layers: Vec<Vec<u8>>) -> Result<Self, String> {
+        let layer_size = check_size(width, height)?;
+        if layers.is_empty() {
+            return Err("An image needs at least one layer".to_owned());
+        }
+
+        for (index, layer) in layers.iter().enumerate() {
+            if layer.len() != layer_size {
+                return Err(format!("Layer {} has {} digits, expected {}", index + 1, layer.len(), layer_size));
+            }
+
+            if let Some(digit) = layer.iter().find(|x| **x > 9) {
+                return Err(format!("Layer {} holds {}, which isn't a digit", index + 1, digit));
+            }
+        }
+
+        Ok(SifImage { width, height, layers })
+    }
+
+    /// Encodes a bitmap as a single layer, with set cells white and the rest black
+    pub fn from_bitmap(bitmap: &Grid<bool>) -> Self {
+        let layer = bitmap.iter().map(|(_, lit)| if *lit { WHITE } else { BLACK }).collect();
+        SifImage {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            layers: vec![layer],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn layers(&self) -> &[Vec<u8>] {
+        &self.layers
+    }
+
+    /// Digit counts for each layer, front to back
+    pub fn digit_counts(&self) -> Vec<DigitCounts> {
+        self.layers.iter()
+            .map(|layer| {
+                let mut counts = [0; 10];
+                for digit in layer {
+                    counts[*digit as usize] += 1;
+                }
+
+                DigitCounts(counts)
+            })
+            .collect()
+    }
+
+    /// Stacks the layers, where each pixel takes its color from the first layer that isn't
+    /// transparent there.  Digits other than black, white and transparent are an error.
+    pub fn flatten(&self) -> Result<Grid<Pixel>, String> {
+        let mut result = Grid::new(self.width, self.height, Pixel::Transparent);
+        for index in 0..(self.width * self.height) {
+            let mut pixel = Pixel::Transparent;
+            for (layer_index, layer) in self.layers.iter().enumerate() {
+                pixel = match layer[index] {
+                    BLACK => Pixel::Black,
+                    WHITE => Pixel::White,
+                    TRANSPARENT => continue,
+                    x => return Err(format!("Layer {} has an unknown color {} at pixel {}", layer_index + 1, x, index)),
+                };
+
+                break;
+            }
+
+            result.set(&Point::new(index % self.width, index / self.width), pixel);
+        }
+
+        Ok(result)
+    }
+
+    /// The digits of every layer, front to back
+    pub fn encode(&self) -> String {
+        self.layers.iter()
+            .flat_map(|layer| layer.iter().map(|x| (b'0' + x) as char))
+            .collect()
+    }
+}
+
+fn check_size(width: usize, height: usize) -> Result<usize, String> {
+    if width == 0 || height == 0 {
+        return Err(format!("Images must be at least 1x1, found {}x{}", width, height));
+    }
+
+    Ok(width * height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_layers() {
+        let image = SifImage::decode("123456789012\n", 3, 2).unwrap();
+        assert_eq!(image.layers(), &[vec![1, 2, 3, 4, 5, 6], vec![7, 8, 9, 0, 1, 2]]);
+
+        let counts = image.digit_counts();
+        assert_eq!((counts[0].count(0), counts[1].count(0), counts[1].count(1)), (0, 1, 1));
+        assert_eq!(image.encode(), "123456789012");
+    }
+
+    #[test]
+    fn rejects_bad_data() {
+        assert!(SifImage::decode("1234567", 3, 2).unwrap_err().starts_with("7 digits can't be split"));
+        assert!(SifImage::decode("12a456", 3, 2).unwrap_err().contains("position 3"));
+        assert!(SifImage::decode("", 3, 2).is_err());
+        assert!(SifImage::decode("1", 0, 1).is_err());
+        assert!(SifImage::from_layers(2, 1, vec![vec![0, 1], vec![0]]).is_err());
+    }
+
+    #[test]
+    fn flattens_with_transparency() {
+        let image = SifImage::decode("0222112222120000", 2, 2).unwrap();
+        let flat = image.flatten().unwrap();
+        assert_eq!(flat.render(|x| if *x == Pixel::White { '#' } else { '.' }), ".#\n#.");
+
+        let see_through = SifImage::decode("2212", 2, 1).unwrap().flatten().unwrap();
+        assert_eq!(see_through.get(&Point::new(0, 0)), Some(&Pixel::White));
+        assert_eq!(see_through.get(&Point::new(1, 0)), Some(&Pixel::Transparent));
+        assert!(SifImage::decode("27", 2, 1).unwrap().flatten().is_err());
+    }
+
+    #[test]
+    fn encodes_bitmaps() {
+        let bitmap = Grid::parse("#.\n.#", |ch| ch == '#');
+        let image = SifImage::from_bitmap(&bitmap);
+        assert_eq!(image.encode(), "1001");
+        assert_eq!(image.flatten().unwrap().render(|x| if *x == Pixel::White { '#' } else { '.' }), "#.\n.#");
+    }
+}
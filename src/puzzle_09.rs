@@ -1,12 +1,34 @@
-use crate::intcode::Machine;
+use crate::intcode::{parse_program, Machine, MachineState};
+use crate::puzzle::Puzzle;
 
-pub fn run () {
-    //let input = vec![104,1125899906842624,99];
-    //let mut machine = Machine::new_from_memory(input);
-    let mut machine = Machine::new_from_file("src/inputs/09A.txt");
-    machine.input_buffer.push_front(2);
+pub struct Day09;
+
+impl Puzzle for Day09 {
+    type Input = Vec<i128>;
+    type Part1 = i128;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Vec<i128>) -> i128 {
+        run_boost(program, 1)
+    }
+
+    fn part2(program: &Vec<i128>) -> i128 {
+        run_boost(program, 2)
+    }
+}
+
+fn run_boost(program: &[i128], mode: i128) -> i128 {
+    let mut machine = Machine::new_from_memory(program.to_vec());
+    machine.input_buffer.push_front(mode);
     let stop_reason = machine.run_program();
 
-    println!("{:?}", stop_reason);
-    println!("Output: {:?}", machine.output_buffer);
-}
\ No newline at end of file
+    if stop_reason != MachineState::Halted || machine.output_buffer.len() != 1 {
+        panic!("BOOST program failed ({:?}), output: {:?}", stop_reason, machine.output_buffer);
+    }
+
+    machine.output_buffer[0]
+}
@@ -1,36 +1,77 @@
+use crate::geometry::{Grid, Point};
+use crate::puzzle::Puzzle;
 use std::f32;
 use std::collections::HashSet;
 
-pub fn run() {
-    let input = read_input();
-    let points = get_points(input);
+pub struct Day10;
 
+impl Puzzle for Day10 {
+    type Input = Vec<Point<i32>>;
+    type Part1 = usize;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Vec<Point<i32>> {
+        get_points(input)
+    }
+
+    fn part1(points: &Vec<Point<i32>>) -> usize {
+        get_best_station(points).len()
+    }
+
+    fn part2(points: &Vec<Point<i32>>) -> i32 {
+        let references = sort_refs(get_best_station(points));
+        let first_index = references.iter().position(|x| x.angle >= 270_f32).unwrap_or(0);
+
+        let mut destroyed_points = HashSet::new();
+        let mut hit_count = 0;
+        let mut current_index = first_index;
+        while hit_count < points.len() - 1 {
+            let reference = references.get(current_index).unwrap();
+            for point in &reference.points {
+                if !destroyed_points.contains(point) {
+                    destroyed_points.insert(*point);
+                    hit_count += 1;
+
+                    if hit_count == 200 {
+                        return point.x * 100 + point.y;
+                    }
+
+                    break;
+                }
+            }
+
+            current_index += 1;
+            if current_index >= references.len() {
+                current_index = 0;
+            }
+        }
+
+        panic!("Only {} asteroids could be destroyed", hit_count);
+    }
+}
+
+/// Finds the asteroid that can see the most other asteroids, returning one reference per
+/// direction it can see
+fn get_best_station(points: &Vec<Point<i32>>) -> Vec<Reference> {
     let mut candidate = None;
     let mut visible_count = 0;
 
-    for point1 in &points {
+    for point1 in points {
         let mut references: Vec<Reference> = Vec::new();
-        for point2 in &points {
+        for point2 in points {
             if point1 == point2 {
                 continue;
             }
 
             let angle = get_angle(point1, point2);
 
-            let mut reference_index = None;
-            for x in 0..references.len() {
-                let difference = (references[x].angle - angle).abs();
-                if difference < f32::EPSILON {
-                    reference_index = Some(x);
-                    break;
-                }
-            }
+            let reference_index = references.iter().position(|x| (x.angle - angle).abs() < f32::EPSILON);
 
             let reference = if let Some(x) = reference_index {
                 references.get_mut(x).unwrap()
             } else {
                 references.push(Reference {
-                    origin: point1.clone(),
+                    origin: *point1,
                     angle,
                     points: Vec::new(),
                 });
@@ -39,7 +80,7 @@ pub fn run() {
                 references.get_mut(index).unwrap()
             };
 
-            reference.points.push(point2.clone());
+            reference.points.push(*point2);
         }
 
         if visible_count < references.len() {
@@ -48,109 +89,24 @@ pub fn run() {
         }
     }
 
-    println!("total: {}", visible_count);
-
-    let references = sort_refs(candidate.unwrap());
-    let mut first_index = 0;
-    for x in 0..references.len() {
-        if references[x].angle >= 270_f32 {
-            first_index = x;
-            break;
-        }
-    }
-
-    let mut destroyed_points = HashSet::new();
-    let mut hit_count = 0;
-    let mut current_index = first_index;
-    while hit_count < visible_count {
-        let reference = references.get(current_index).unwrap();
-        for point in &reference.points {
-            if !destroyed_points.contains(point) {
-                destroyed_points.insert(point.clone());
-                hit_count = hit_count + 1;
-
-                println!("{}: {:?}", hit_count, point);
-                break;
-            }
-        }
-
-        current_index = current_index + 1;
-        if current_index >= references.len() {
-            current_index = 0;
-        }
-    }
-}
-
-#[derive(Debug, Eq, PartialEq, Clone, Hash)]
-struct Point {
-    x: i32,
-    y: i32,
+    candidate.unwrap()
 }
 
 #[derive(Debug)]
 struct Reference {
-    origin: Point,
+    origin: Point<i32>,
     angle: f32,
-    points: Vec<Point>,
+    points: Vec<Point<i32>>,
 }
 
-fn read_input() -> String {
-    "#..#....#...#.#..#.......##.#.####
-#......#..#.#..####.....#..#...##.
-.##.......#..#.#....#.#..#.#....#.
-###..#.....###.#....##.....#...#..
-...#.##..#.###.......#....#....###
-.####...##...........##..#..#.##..
-..#...#.#.#.###....#.#...##.....#.
-......#.....#..#...##.#..##.#..###
-...###.#....#..##.#.#.#....#...###
-..#.###.####..###.#.##..#.##.###..
-...##...#.#..##.#............##.##
-....#.##.##.##..#......##.........
-.#..#.#..#.##......##...#.#.#...##
-.##.....#.#.##...#.#.#...#..###...
-#.#.#..##......#...#...#.......#..
-#.......#..#####.###.#..#..#.#.#..
-.#......##......##...#..#..#..###.
-#.#...#..#....##.#....#.##.#....#.
-....#..#....##..#...##..#..#.#.##.
-#.#.#.#.##.#.#..###.......#....###
-...#.#..##....###.####.#..#.#..#..
-#....##..#...##.#.#.........##.#..
-.#....#.#...#.#.........#..#......
-...#..###...#...#.#.#...#.#..##.##
-.####.##.#..#.#.#.#...#.##......#.
-.##....##..#.#.#.......#.....####.
-#.##.##....#...#..#.#..###..#.###.
-...###.#..#.....#.#.#.#....#....#.
-......#...#.........##....#....##.
-.....#.....#..#.##.#.###.#..##....
-.#.....#.#.....#####.....##..#....
-.####.##...#.......####..#....##..
-.#.#.......#......#.##..##.#.#..##
-......##.....##...##.##...##......".to_owned()
-}
-
-fn get_points(input: String) -> Vec<Point> {
-    let mut results = Vec::new();
-    let mut y = 0;
-    for line in input.lines() {
-        let mut x = 0;
-        for char in line.chars() {
-            if char == '#' {
-                results.push(Point {x, y});
-            }
-
-            x = x + 1;
-        }
-
-        y = y + 1;
-    }
-
-    results
+fn get_points(input: &str) -> Vec<Point<i32>> {
+    Grid::parse(input, |ch| ch == '#').iter()
+        .filter(|(_, is_asteroid)| **is_asteroid)
+        .map(|(point, _)| Point::new(point.x as i32, point.y as i32))
+        .collect()
 }
 
-fn get_angle(point1: &Point, point2: &Point) -> f32 {
+fn get_angle(point1: &Point<i32>, point2: &Point<i32>) -> f32 {
     let distance_x = (point2.x - point1.x) as f32;
     let distance_y = (point2.y - point1.y) as f32;
     let radians = distance_y.atan2(distance_x);
@@ -165,7 +121,7 @@ fn get_angle(point1: &Point, point2: &Point) -> f32 {
     }
 }
 
-fn get_distance(point1: &Point, point2: &Point) -> f32 {
+fn get_distance(point1: &Point<i32>, point2: &Point<i32>) -> f32 {
     let distance_x = point2.x - point1.x;
     let distance_y = point2.y - point1.y;
 
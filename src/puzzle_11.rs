@@ -1,26 +1,50 @@
-use crate::intcode::{Machine, MachineState};
-use std::collections::{HashSet, HashMap};
+use crate::geometry::{Direction, Point, SparseGrid, Turn};
+use crate::image::{self, Image};
+use crate::intcode::{parse_program, Machine, MachineState};
+use crate::ocr;
+use crate::puzzle::Puzzle;
 
-enum Direction { Up, Down, Left, Right }
-
-#[derive(Clone)]
+#[derive(Clone, Eq, PartialEq)]
 enum Color { Black, White }
 
-#[derive(Eq, PartialEq, Hash, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
+pub struct Day11;
+
+impl Puzzle for Day11 {
+    type Input = Vec<i128>;
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Vec<i128>) -> usize {
+        paint(program, Color::Black).len()
+    }
+
+    fn part2(program: &Vec<i128>) -> String {
+        let image = paint(program, Color::White).to_grid(Color::Black);
+        ocr::recognize(&image, |color| *color == Color::White).unwrap_or_else(|message| panic!("{}", message))
+    }
 }
 
-pub fn run() {
-    let mut machine = Machine::new_from_file("src/inputs/11A.txt");
-    let mut current_direction = Direction::Up;
-    let mut visited_points = HashSet::new();
-    let mut point_colors = HashMap::new();
-    let mut current_point = Point { x: 0, y: 0 };
+/// The registration identifier painted on the hull, as white paint on black
+pub fn render_image(input: &str) -> Image {
+    let hull = paint(&Day11::parse(input), Color::White).to_grid(Color::Black);
+    Image::from_grid(&hull, |color| match color {
+        Color::White => image::WHITE,
+        Color::Black => image::BLACK,
+    })
+}
+
+/// Runs the painting robot over the hull, starting on a panel of the specified color, and
+/// returns every panel that was painted
+fn paint(program: &[i128], starting_color: Color) -> SparseGrid<i32, Color> {
+    let mut machine = Machine::new_from_memory(program.to_vec());
+    let mut current_direction = Direction::North;
+    let mut point_colors = SparseGrid::new();
+    let mut current_point = Point::origin();
     let mut has_moved_once = false;
-    let mut max_coords = Point { x: 0, y: 0 };
-    let mut min_coords = Point { x: 0, y: 0 };
 
     loop {
         let state = machine.run_program();
@@ -31,54 +55,28 @@ pub fn run() {
                 x => panic!("Invalid color code {}", x),
             };
 
-            let direction_code = machine.output_buffer.remove(0).unwrap();
-            if direction_code != 0 && direction_code != 1 {
-                panic!("Invalid direction code {}", direction_code);
-            }
-
-            current_direction = match current_direction {
-                Direction::Up => if direction_code == 0 { Direction::Left } else { Direction:: Right },
-                Direction::Down => if direction_code == 0 { Direction::Right } else { Direction::Left },
-                Direction::Left => if direction_code == 0 { Direction::Down } else { Direction::Up },
-                Direction::Right => if direction_code == 0 { Direction::Up } else { Direction::Down },
+            let turn = match machine.output_buffer.remove(0).unwrap() {
+                0 => Turn::Left,
+                1 => Turn::Right,
+                x => panic!("Invalid direction code {}", x),
             };
 
+            current_direction = current_direction.turn(turn);
+
             // Paint the tile
-            visited_points.insert(current_point.clone());
-            point_colors.insert(current_point.clone(), color);
+            point_colors.insert(current_point, color);
 
             // Move
-            current_point = match current_direction {
-                Direction::Up => Point { x: current_point.x, y: current_point.y + 1 },
-                Direction::Down => Point { x: current_point.x, y: current_point.y - 1 },
-                Direction::Left => Point { x: current_point.x - 1, y: current_point.y },
-                Direction::Right => Point { x: current_point.x + 1, y: current_point.y },
-            };
-
-            if current_point.x > max_coords.x {
-                max_coords = Point { x: current_point.x, y: max_coords.y }
-            }
-
-            if current_point.y > max_coords.y {
-                max_coords = Point { x: max_coords.x, y: current_point.y }
-            }
-
-            if current_point.x < min_coords.x {
-                min_coords = Point { x: current_point.x, y: min_coords.y }
-            }
-
-            if current_point.y < min_coords.y {
-                min_coords = Point { x: min_coords.x, y: current_point.y }
-            }
+            current_point = current_point.step(current_direction);
         }
 
         match state {
             MachineState::Halted => break,
             MachineState::WaitingForInput => {
                 let input_value = match point_colors.get(&current_point) {
-                    None => match has_moved_once {
-                        true => 0,
-                        false => 1, // first tile starts white
+                    None => match (has_moved_once, &starting_color) {
+                        (false, Color::White) => 1,
+                        _ => 0,
                     },
                     Some(color) => match color {
                         Color::Black => 0,
@@ -93,27 +91,5 @@ pub fn run() {
         has_moved_once = true;
     }
 
-    println!("Visited {} points", visited_points.len());
-    println!();
-
-    render(min_coords, max_coords, point_colors);
+    point_colors
 }
-
-fn render(min_coords: Point, max_coords: Point, color_map: HashMap<Point, Color>) {
-    for y in (min_coords.y..=max_coords.y).rev() {
-        for x in min_coords.x..=max_coords.x {
-            let point = Point { x, y };
-            let color = match color_map.get(&point) {
-                None => Color::Black,
-                Some(color) => color.clone(),
-            };
-
-            match color {
-                Color::Black => print!(" "),
-                Color::White => print!("#"),
-            }
-        }
-
-        println!();
-    }
-}
\ No newline at end of file
@@ -1,8 +1,24 @@
+use crate::puzzle::Puzzle;
 use regex::Regex;
 
-pub fn run() {
-    let mut moons = read_moons();
-    part_2(&mut moons);
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    type Input = Vec<Moon>;
+    type Part1 = i32;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<Moon> {
+        read_moons(input)
+    }
+
+    fn part1(moons: &Vec<Moon>) -> i32 {
+        total_energy(moons, 1000)
+    }
+
+    fn part2(moons: &Vec<Moon>) -> i128 {
+        steps_until_repeat(moons)
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -13,39 +29,39 @@ struct Tuple {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
-struct Moon {
+pub struct Moon {
     position: Tuple,
     velocity: Tuple,
 }
 
-fn part_1(moons: &mut Vec<Moon>) {
-    for step in 0..1001 {
-        if step > 0 {
-            apply_gravity(moons);
-            update_position(moons);
-        }
-
-        println!("After {} steps", step);
-        for moon in moons.iter() {
-            let potential = moon.position.x.abs() + moon.position.y.abs() + moon.position.z.abs();
-            let kinetic = moon.velocity.x.abs() + moon.velocity.y.abs() + moon.velocity.z.abs();
-            let total = potential * kinetic;
+/// Total energy in the system after simulating the specified number of steps
+fn total_energy(moons: &[Moon], steps: usize) -> i32 {
+    let mut simulation = moons.to_vec();
+    for _ in 0..steps {
+        apply_gravity(&mut simulation);
+        update_position(&mut simulation);
+    }
 
-            println!("{:?}", moon);
-            println!("pot: {}, kin: {}, total: {}", potential, kinetic, total);
-        }
-        println!();
+    let mut total = 0;
+    for moon in &simulation {
+        let potential = moon.position.x.abs() + moon.position.y.abs() + moon.position.z.abs();
+        let kinetic = moon.velocity.x.abs() + moon.velocity.y.abs() + moon.velocity.z.abs();
+        total += potential * kinetic;
     }
+
+    total
 }
 
 #[derive(Eq, PartialEq, Debug)]
 enum Axis { X, Y, Z }
 
-fn part_2(moons: &mut Vec<Moon>) {
+/// Number of steps until the moons return to a previous state, found by looping each axis on
+/// its own since the axes don't affect each other
+fn steps_until_repeat(moons: &[Moon]) -> i128 {
     let mut loop_rates = Vec::new();
     for axis in &[Axis::X, Axis::Y, Axis::Z] {
         let mut steps = 0;
-        let mut simulation = moons.clone();
+        let mut simulation = moons.to_vec();
         loop {
             if steps > 0 {
                 let mut is_match = false;
@@ -62,28 +78,21 @@ fn part_2(moons: &mut Vec<Moon>) {
                 }
 
                 if is_match {
-                    println!("1 {:?} rotation in {} steps", axis, steps);
                     loop_rates.push(steps as i128);
                     break;
                 }
             }
 
-            steps = steps + 1;
+            steps += 1;
             apply_gravity(&mut simulation);
             update_position(&mut simulation);
         }
     }
 
-    let test = lcm(loop_rates[0], lcm(loop_rates[1], loop_rates[2]));
-    println!("Least common multiple: {}", test);
+    lcm(loop_rates[0], lcm(loop_rates[1], loop_rates[2]))
 }
 
-fn read_moons() -> Vec<Moon> {
-    let input = "<x=-13, y=14, z=-7>
-<x=-18, y=9, z=0>
-<x=0, y=-3, z=-3>
-<x=-15, y=3, z=-13>";
-
+fn read_moons(input: &str) -> Vec<Moon> {
     let mut moons = Vec::new();
     let regex = Regex::new(r"<x=([0-9|-]*), y=([0-9|-]*), z=([0-9|-]*)>").unwrap();
     for line in input.lines() {
@@ -105,41 +114,41 @@ fn read_moons() -> Vec<Moon> {
     moons
 }
 
-fn apply_gravity(moons: &mut Vec<Moon>) {
+fn apply_gravity(moons: &mut [Moon]) {
     for x in 0..moons.len() {
         for y in (x + 1)..moons.len() {
             if moons[x].position.x < moons[y].position.x {
-                moons[x].velocity.x = moons[x].velocity.x + 1;
-                moons[y].velocity.x = moons[y].velocity.x - 1;
+                moons[x].velocity.x += 1;
+                moons[y].velocity.x -= 1;
             } else if moons[x].position.x > moons[y].position.x {
-                moons[x].velocity.x = moons[x].velocity.x - 1;
-                moons[y].velocity.x = moons[y].velocity.x + 1;
+                moons[x].velocity.x -= 1;
+                moons[y].velocity.x += 1;
             }
 
             if moons[x].position.y < moons[y].position.y {
-                moons[x].velocity.y = moons[x].velocity.y + 1;
-                moons[y].velocity.y = moons[y].velocity.y - 1;
+                moons[x].velocity.y += 1;
+                moons[y].velocity.y -= 1;
             } else if moons[x].position.y > moons[y].position.y {
-                moons[x].velocity.y = moons[x].velocity.y - 1;
-                moons[y].velocity.y = moons[y].velocity.y + 1;
+                moons[x].velocity.y -= 1;
+                moons[y].velocity.y += 1;
             }
 
             if moons[x].position.z < moons[y].position.z {
-                moons[x].velocity.z = moons[x].velocity.z + 1;
-                moons[y].velocity.z = moons[y].velocity.z - 1;
+                moons[x].velocity.z += 1;
+                moons[y].velocity.z -= 1;
             } else if moons[x].position.z > moons[y].position.z {
-                moons[x].velocity.z = moons[x].velocity.z - 1;
-                moons[y].velocity.z = moons[y].velocity.z + 1;
+                moons[x].velocity.z -= 1;
+                moons[y].velocity.z += 1;
             }
         }
     }
 }
 
-fn update_position(moons: &mut Vec<Moon>) {
+fn update_position(moons: &mut [Moon]) {
     for moon in moons {
-        moon.position.x = moon.position.x + moon.velocity.x;
-        moon.position.y = moon.position.y + moon.velocity.y;
-        moon.position.z = moon.position.z + moon.velocity.z;
+        moon.position.x += moon.velocity.x;
+        moon.position.y += moon.velocity.y;
+        moon.position.z += moon.velocity.z;
     }
 }
 
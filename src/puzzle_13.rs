@@ -1,106 +1,278 @@
-use crate::intcode::{Machine, MachineState};
+use crate::display::{Display, Palette, Swatch, TerminalScreen, Update};
+use crate::geometry::Point;
+use crate::input;
+use crate::intcode::{parse_program, Machine, MachineState};
+use crate::puzzle::Puzzle;
+use self::controllers::{BallTracker, KeyboardController, PaddleController, ScriptedController, TrajectoryPredictor};
+use self::replay::Recorder;
 use console::{Term};
-use std::io::Write;
-use std::collections::{HashSet};
+use std::fs;
 use std::{thread, time};
 
+const SCORE_CHANNEL: &str = "Score";
+const WALL_TILE: i128 = 1;
+const BLOCK_TILE: i128 = 2;
+const PADDLE_TILE: i128 = 3;
+const BALL_TILE: i128 = 4;
+
+pub mod controllers;
+pub mod memory;
+pub mod replay;
+
+pub struct GameResult {
+    pub score: i128,
+    pub blocks_remaining: usize,
+    pub frames: usize,
+}
+
+/// What a controller can see of the game when deciding its next move
+pub struct GameState {
+    pub paddle_position: Point<i128>,
+    pub ball_position: Point<i128>,
+    pub score: i128,
+    pub blocks_remaining: usize,
+    pub frame: usize,
+}
+
+/// A game in progress, tracking the screen and where the paddle and ball were last drawn
+pub struct Game {
+    pub machine: Machine,
+    pub display: Display,
+    paddle_position: Point<i128>,
+    ball_position: Point<i128>,
+    frames: usize,
+}
+
+/// Watches the game as it's played
+pub trait Viewer {
+    fn start(&mut self) {}
+    fn frame(&mut self, display: &Display, updates: &[Update]);
+    fn finish(&mut self, _display: &Display) {}
+}
+
+/// Shows the game on a terminal as it's played, pausing after every frame so it's watchable
+pub struct TerminalViewer {
+    screen: TerminalScreen,
+    frame_delay: time::Duration,
+}
+
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    type Input = Vec<i128>;
+    type Part1 = usize;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    /// Number of block tiles on the screen when the game exits without quarters
+    fn part1(program: &Vec<i128>) -> usize {
+        let mut game = Game::new(Machine::new_from_memory(program.to_vec()));
+        game.next_frame();
+        game.display.count(BLOCK_TILE)
+    }
+
+    fn part2(program: &Vec<i128>) -> i128 {
+        play(insert_quarters(program), &mut TrajectoryPredictor::new(), None).score
+    }
+}
+
 pub fn run() {
-    let mut machine = Machine::new_from_file("src/inputs/13A.txt");
+    let mut viewer = TerminalViewer::new(Term::stdout(), time::Duration::from_millis(5));
+    play(load_game(), &mut TrajectoryPredictor::new(), Some(&mut viewer));
+}
+
+pub fn run_with_keyboard() {
+    let mut viewer = TerminalViewer::new(Term::stdout(), time::Duration::from_millis(0));
+    let mut controller = KeyboardController::new(Term::stdout());
+    play(load_game(), &mut controller, Some(&mut viewer));
+}
+
+pub fn run_headless() {
+    let result = play(load_game(), &mut TrajectoryPredictor::new(), None);
+    println!("Final score: {}", result.score);
+    println!("Blocks remaining: {}", result.blocks_remaining);
+    println!("Frames: {}", result.frames);
+}
+
+/// Records the game and saves it as an asciinema cast and an animated GIF
+pub fn record_replay() -> Result<(), String> {
+    let mut recorder = Recorder::new();
+    play(load_game(), &mut TrajectoryPredictor::new(), Some(&mut recorder));
+
+    fs::write("breakout.cast", recorder.to_asciicast(0.02)?)
+        .map_err(|error| format!("Could not write breakout.cast: {}", error))?;
+    fs::write("breakout.gif", recorder.to_gif(4, 2)?)
+        .map_err(|error| format!("Could not write breakout.gif: {}", error))?;
+
+    println!("Recorded {} frames to breakout.cast and breakout.gif", recorder.frames.len());
+    Ok(())
+}
+
+/// Finds where the game keeps its state and prints the addresses
+pub fn print_memory_layout() -> Result<(), String> {
+    let layout = memory::locate_game_state(&load_game())?;
+    println!("Score: {}", layout.score);
+    println!("Paddle x: {} (row {})", layout.paddle_x, layout.paddle_row);
+    println!("Ball x: {}, y: {}", layout.ball_x, layout.ball_y);
+    println!("Grid: {}x{} starting at {}", layout.grid.width, layout.grid.height, layout.grid.start);
+    Ok(())
+}
+
+/// Gets the final score without steering, by turning the paddle's row into a wall first
+pub fn run_with_paddle_wall() -> Result<(), String> {
+    let mut machine = load_game();
+    let layout = memory::locate_game_state(&machine)?;
+    memory::widen_paddle(&mut machine, &layout);
+
+    let result = play(machine, &mut ScriptedController::new(Vec::new()), None);
+    println!("Final score: {} ({} frames)", result.score, result.frames);
+    Ok(())
+}
+
+/// Plays the game once with each automatic controller and reports how quickly each one won
+pub fn compare_controllers() {
+    let mut controllers: Vec<(&str, Box<dyn PaddleController>)> = vec![
+        ("Trajectory predictor", Box::new(TrajectoryPredictor::new())),
+        ("Ball tracker", Box::new(BallTracker)),
+    ];
+
+    for (name, controller) in controllers.iter_mut() {
+        let result = play(load_game(), controller.as_mut(), None);
+        if result.is_won() {
+            println!("{}: won in {} frames (score {})", name, result.frames, result.score);
+        } else {
+            println!("{}: lost after {} frames with {} blocks remaining", name, result.frames, result.blocks_remaining);
+        }
+    }
+}
+
+/// Loads the game with quarters inserted so it can be played
+pub fn load_game() -> Machine {
+    insert_quarters(&Day13::parse(&input::read_day(13)))
+}
+
+fn insert_quarters(program: &[i128]) -> Machine {
+    let mut machine = Machine::new_from_memory(program.to_vec());
     machine.memory[0] = 2;
-    let mut term = Term::stdout();
-    term.clear_screen().unwrap();
+    machine
+}
 
-    let mut last_y_coord: usize = 0;
-    let mut score = 0;
-    let mut blocks = HashSet::new();
-    let mut paddle_position = Position {from_left: 0, from_top: 0 };
-    let mut ball_position = Position {from_left: 0, from_top: 0 };
-    let mut ball_direction = Direction::Right;
+/// Plays the game until the program halts
+pub fn play(machine: Machine,
+            controller: &mut dyn PaddleController,
+            mut viewer: Option<&mut dyn Viewer>) -> GameResult {
+    let mut game = Game::new(machine);
 
-    render_score(&mut term, score);
+    if let Some(viewer) = viewer.as_mut() {
+        viewer.start();
+    }
 
     loop {
-        let state = machine.run_program();
-        while machine.output_buffer.len() >= 3 {
-            if machine.output_buffer.len() < 3 {
-                break;
-            }
+        let (state, updates) = game.next_frame();
+        if let Some(viewer) = viewer.as_mut() {
+            viewer.frame(&game.display, &updates);
+        }
 
-            let values = machine.output_buffer.drain(0..3).collect::<Vec<i128>>();
-            if values[0] == -1 && values[1] == 0 {
-                // score
-                score = values[2];
-                render_score(&mut term, score);
-            } else {
-                if values[1] as usize > last_y_coord {
-                    last_y_coord = values[1] as usize;
-                }
+        if state == MachineState::Halted {
+            break;
+        }
+
+        let next_input = controller.next_input(&game.state());
+        game.machine.input_buffer.push_front(next_input);
+    }
 
-                let position = Position {
-                    from_left: values[0],
-                    from_top: values[1],
-                };
-
-                let tile_type = match values[2] {
-                    0 => Tile::Empty,
-                    1 => Tile::Wall,
-                    2 => Tile::Block,
-                    3 => Tile::Paddle,
-                    4 => Tile::Ball,
-                    x => panic!("Unknown tile type {}", x),
-                };
-
-                render_tile(&mut term, &tile_type, &position);
-
-                if tile_type == Tile::Block {
-                    blocks.insert(position);
-                } else if tile_type == Tile::Empty {
-                    blocks.remove(&position);
-                } else if tile_type == Tile::Paddle {
-                    paddle_position = position
-                } else if tile_type == Tile::Ball {
-                    ball_direction = if position.from_left < ball_position.from_left {
-                        Direction::Left
-                    } else {
-                        Direction::Right
-                    };
-
-                    ball_position = position;
+    if let Some(viewer) = viewer {
+        viewer.finish(&game.display);
+    }
+
+    game.result()
+}
+
+impl Game {
+    pub fn new(machine: Machine) -> Self {
+        Game {
+            machine,
+            display: create_display(),
+            paddle_position: Point::origin(),
+            ball_position: Point::origin(),
+            frames: 0,
+        }
+    }
+
+    /// Runs the program until it asks for the next joystick input or halts.  Each frame is
+    /// everything drawn between two joystick inputs.
+    pub fn next_frame(&mut self) -> (MachineState, Vec<Update>) {
+        let state = self.machine.run_program();
+        let updates = self.display.consume(&mut self.machine.output_buffer);
+        for update in &updates {
+            if let Update::Pixel { x, y, value } = update {
+                let position = Point::new(*x as i128, *y as i128);
+
+                match get_tile(*value) {
+                    Tile::Paddle => self.paddle_position = position,
+                    Tile::Ball => self.ball_position = position,
+                    _ => (),
                 }
             }
         }
 
-        let distance = paddle_position.from_top - ball_position.from_top - 1;
-        let target = match ball_direction {
-            Direction::Left => ball_position.from_left - distance,
-            Direction::Right => ball_position.from_left + distance,
-        };
+        self.frames += 1;
+        (state, updates)
+    }
 
-        let next_input = if paddle_position.from_left < target {
-            1 // go right
-        } else if paddle_position.from_left > target {
-            -1 // go left
-        } else {
-            0
-        };
+    pub fn state(&self) -> GameState {
+        GameState {
+            paddle_position: self.paddle_position,
+            ball_position: self.ball_position,
+            score: self.display.channel(SCORE_CHANNEL).unwrap_or(0),
+            blocks_remaining: self.display.count(BLOCK_TILE),
+            frame: self.frames,
+        }
+    }
 
-        //term.move_cursor_to(0, last_y_coord + 4).unwrap();
-        //println!("Blocks: {}", blocks.len());
-        //println!("Ball Position: {:?}", ball_position);
-        //println!("Ball Direction: {:?}", ball_direction);
-        //println!("Paddle Position: {:?}", paddle_position);
-        //println!("Target: {:?} ({})", target, distance);
+    pub fn result(&self) -> GameResult {
+        GameResult {
+            score: self.display.channel(SCORE_CHANNEL).unwrap_or(0),
+            blocks_remaining: self.display.count(BLOCK_TILE),
+            frames: self.frames,
+        }
+    }
+}
 
-        machine.input_buffer.push_front(next_input);
-        thread::sleep(time::Duration::from_millis(5));
+impl GameResult {
+    pub fn is_won(&self) -> bool {
+        self.blocks_remaining == 0
+    }
+}
 
-        if state == MachineState::Halted {
-            break;
+impl TerminalViewer {
+    pub fn new(term: Term, frame_delay: time::Duration) -> Self {
+        TerminalViewer {
+            screen: TerminalScreen::new(term),
+            frame_delay,
         }
     }
+}
+
+impl Viewer for TerminalViewer {
+    fn start(&mut self) {
+        self.screen.clear();
+    }
+
+    fn frame(&mut self, display: &Display, updates: &[Update]) {
+        for update in updates {
+            self.screen.draw(display, update);
+        }
+
+        thread::sleep(self.frame_delay);
+    }
 
-    term.move_cursor_to(0, last_y_coord + 4).unwrap();
-    term.write_line("Program halted").unwrap();
+    fn finish(&mut self, display: &Display) {
+        self.screen.write_below(display, "Program halted");
+    }
 }
 
 #[derive(Eq, PartialEq)]
@@ -112,28 +284,88 @@ enum Tile {
     Ball,
 }
 
-#[derive(Hash, Eq, PartialEq, Debug)]
-struct Position {
-    from_left: i128,
-    from_top: i128,
+fn get_tile(value: i128) -> Tile {
+    match value {
+        0 => Tile::Empty,
+        1 => Tile::Wall,
+        2 => Tile::Block,
+        3 => Tile::Paddle,
+        4 => Tile::Ball,
+        x => panic!("Unknown tile type {}", x),
+    }
 }
 
-#[derive(Eq, PartialEq, Debug)]
-enum Direction { Left, Right }
+fn create_display() -> Display {
+    let mut palette = Palette::new(Swatch { symbol: ' ', color: (0, 0, 0) });
+    palette.set(WALL_TILE, '█', (128, 128, 128));
+    palette.set(BLOCK_TILE, 'X', (200, 80, 40));
+    palette.set(PADDLE_TILE, '_', (240, 240, 240));
+    palette.set(BALL_TILE, 'O', (250, 220, 60));
 
-fn render_score(term: &mut Term, score: i128) {
-    term.move_cursor_to(0, 1).unwrap();
-    term.write(format!("Score: {}", score).as_bytes()).unwrap();
+    let mut display = Display::new(palette);
+    display.add_channel(SCORE_CHANNEL, -1, 0);
+    display
 }
 
-fn render_tile(term: &mut Term, tile_type: &Tile, position: &Position) {
-    term.move_cursor_to(position.from_left as usize, (position.from_top + 2) as usize).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headless_game_clears_every_block() {
+        let result = play(load_game(), &mut TrajectoryPredictor::new(), None);
+
+        assert_eq!(result.blocks_remaining, 0);
+        assert_eq!(result.score, 13989);
+        assert!(result.frames > 0);
+    }
+
+    #[test]
+    fn ball_tracker_wins_with_same_score() {
+        let result = play(load_game(), &mut BallTracker, None);
 
-    match tile_type {
-        Tile::Empty => term.write(" ".as_bytes()).unwrap(),
-        Tile::Wall => term.write("█".as_bytes()).unwrap(),
-        Tile::Block => term.write("X".as_bytes()).unwrap(),
-        Tile::Paddle => term.write("_".as_bytes()).unwrap(),
-        Tile::Ball => term.write("O".as_bytes()).unwrap(),
-    };
-}
\ No newline at end of file
+        assert!(result.is_won());
+        assert_eq!(result.score, 13989);
+    }
+
+    #[test]
+    fn scripted_controller_replays_inputs_then_stays_still() {
+        let mut controller = ScriptedController::new(vec![1, -1]);
+        let state = GameState {
+            paddle_position: Point::origin(),
+            ball_position: Point::new(5, 0),
+            score: 0,
+            blocks_remaining: 0,
+            frame: 0,
+        };
+
+        let inputs: Vec<i128> = (0..4).map(|_| controller.next_input(&state)).collect();
+        assert_eq!(inputs, vec![1, -1, 0, 0]);
+    }
+
+    #[test]
+    fn recorder_captures_every_frame() {
+        let mut recorder = Recorder::new();
+        let result = play(load_game(), &mut TrajectoryPredictor::new(), Some(&mut recorder));
+        assert_eq!(recorder.frames.len(), result.frames);
+        assert_eq!(recorder.frames.last().unwrap().score, result.score);
+
+        let cast = recorder.to_asciicast(0.1).unwrap();
+        let mut lines = cast.lines();
+        assert!(lines.next().unwrap().starts_with("{\"version\": 2, \"width\": 42, \"height\": 26"));
+        assert!(lines.next().unwrap().starts_with("[0.000, \"o\", \"\\u001b[2J\\u001b[HScore: 0\\r\\n"));
+        assert_eq!(cast.lines().count(), result.frames + 1);
+
+        recorder.frames.truncate(50);
+        let gif = recorder.to_gif(1, 2).unwrap();
+        assert_eq!(&gif[0..6], b"GIF89a");
+        assert_eq!(*gif.last().unwrap(), 0x3B);
+    }
+
+    #[test]
+    fn empty_recording_cannot_be_exported() {
+        let recorder = Recorder::new();
+        assert_eq!(recorder.to_asciicast(0.1), Err("No frames have been recorded".to_owned()));
+        assert_eq!(recorder.to_gif(1, 2), Err("No frames have been recorded".to_owned()));
+    }
+}
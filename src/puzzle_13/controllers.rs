@@ -0,0 +1,120 @@
+use super::GameState;
+use crate::geometry::Point;
+use console::{Key, Term};
+
+/// Decides which way to move the joystick before each frame
+pub trait PaddleController {
+    /// Returns -1 to move the paddle left, 1 to move it right, or 0 to leave it in place
+    fn next_input(&mut self, state: &GameState) -> i128;
+}
+
+#[derive(Eq, PartialEq, Debug)]
+enum Direction { Left, Right }
+
+/// Predicts where the ball will be when it reaches the paddle's row, based on the direction it
+/// last moved horizontally, and moves the paddle there ahead of time
+pub struct TrajectoryPredictor {
+    last_ball_position: Option<Point<i128>>,
+    ball_direction: Direction,
+}
+
+/// Keeps the paddle directly under the ball
+pub struct BallTracker;
+
+/// Moves the paddle with the left and right arrow keys.  Any other key leaves it in place.
+pub struct KeyboardController {
+    term: Term,
+}
+
+/// Replays a fixed list of inputs, leaving the paddle in place once they run out
+pub struct ScriptedController {
+    inputs: Vec<i128>,
+    next_index: usize,
+}
+
+impl TrajectoryPredictor {
+    pub fn new() -> Self {
+        TrajectoryPredictor {
+            last_ball_position: None,
+            ball_direction: Direction::Right,
+        }
+    }
+}
+
+impl PaddleController for TrajectoryPredictor {
+    fn next_input(&mut self, state: &GameState) -> i128 {
+        let ball_position = &state.ball_position;
+        let paddle_position = &state.paddle_position;
+
+        if let Some(last_position) = &self.last_ball_position {
+            if ball_position.x < last_position.x {
+                self.ball_direction = Direction::Left;
+            } else if ball_position.x > last_position.x {
+                self.ball_direction = Direction::Right;
+            }
+        }
+
+        self.last_ball_position = Some(*ball_position);
+
+        let distance = paddle_position.y - ball_position.y - 1;
+        let target = match self.ball_direction {
+            Direction::Left => ball_position.x - distance,
+            Direction::Right => ball_position.x + distance,
+        };
+
+        move_towards(paddle_position, target)
+    }
+}
+
+impl PaddleController for BallTracker {
+    fn next_input(&mut self, state: &GameState) -> i128 {
+        move_towards(&state.paddle_position, state.ball_position.x)
+    }
+}
+
+impl KeyboardController {
+    pub fn new(term: Term) -> Self {
+        KeyboardController { term }
+    }
+}
+
+impl PaddleController for KeyboardController {
+    fn next_input(&mut self, _state: &GameState) -> i128 {
+        match self.term.read_key().unwrap() {
+            Key::ArrowLeft => -1,
+            Key::ArrowRight => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl ScriptedController {
+    pub fn new(inputs: Vec<i128>) -> Self {
+        ScriptedController {
+            inputs,
+            next_index: 0,
+        }
+    }
+}
+
+impl PaddleController for ScriptedController {
+    fn next_input(&mut self, _state: &GameState) -> i128 {
+        match self.inputs.get(self.next_index) {
+            None => 0,
+            Some(input) => {
+                self.next_index += 1;
+                *input
+            }
+        }
+    }
+}
+
+fn move_towards(paddle_position: &Point<i128>, target: i128) -> i128 {
+    if paddle_position.x < target {
+        1 // go right
+    } else if paddle_position.x > target {
+        -1 // go left
+    } else {
+        0
+    }
+}
@@ -0,0 +1,248 @@
+use super::controllers::{BallTracker, PaddleController};
+use super::{Game, BALL_TILE, PADDLE_TILE, WALL_TILE};
+use crate::intcode::{Machine, MachineState};
+use std::collections::HashSet;
+
+/// Maximum number of frames to watch before giving up on narrowing down addresses
+const MAX_FRAMES: usize = 5000;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct MemoryChange {
+    pub address: usize,
+    pub before: i128,
+    pub after: i128,
+}
+
+/// Where the game keeps its screen, stored row by row with one tile value per cell
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct GridLocation {
+    pub start: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Addresses in the game's memory that hold its state
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct MemoryLayout {
+    pub score: usize,
+    pub paddle_x: usize,
+    pub paddle_row: usize,
+    pub ball_x: usize,
+    pub ball_y: usize,
+    pub grid: GridLocation,
+}
+
+/// Lists every address whose value differs between the two snapshots.  Memory that only exists
+/// in one snapshot is treated as 0 in the other.
+pub fn diff_memory(before: &[i128], after: &[i128]) -> Vec<MemoryChange> {
+    let mut changes = Vec::new();
+    for address in 0..before.len().max(after.len()) {
+        let before_value = before.get(address).cloned().unwrap_or(0);
+        let after_value = after.get(address).cloned().unwrap_or(0);
+        if before_value != after_value {
+            changes.push(MemoryChange {
+                address,
+                before: before_value,
+                after: after_value,
+            });
+        }
+    }
+
+    changes
+}
+
+/// Plays a copy of the game, diffing memory between frames to find the addresses that hold the
+/// score, paddle and ball, and searching memory for the screen contents to find the block grid.
+pub fn locate_game_state(machine: &Machine) -> Result<MemoryLayout, String> {
+    // Anything past the original image is scratch space the program grew into, not game state
+    let image_size = machine.memory.len();
+    let mut game = Game::new(Machine::new_from_memory(machine.memory.clone()));
+    let mut controller = BallTracker;
+    let mut score_candidates: Option<HashSet<usize>> = None;
+    let mut paddle_candidates: Option<HashSet<usize>> = None;
+    let mut ball_x_candidates: Option<HashSet<usize>> = None;
+    let mut ball_y_candidates: Option<HashSet<usize>> = None;
+    let mut grid = None;
+    let mut paddle_row = 0;
+
+    for _ in 0..MAX_FRAMES {
+        let previous_state = game.state();
+        let before = game.machine.memory.clone();
+        let (state, _) = game.next_frame();
+        let current_state = game.state();
+        let changes = diff_memory(&before[..image_size], &game.machine.memory[..image_size]);
+
+        if grid.is_none() {
+            // The whole screen is drawn in the first frame
+            grid = Some(find_grid(&game)?);
+            paddle_row = current_state.paddle_position.y as usize;
+        } else {
+            if current_state.score != previous_state.score {
+                narrow(&mut score_candidates, &changes, current_state.score);
+            }
+
+            if current_state.paddle_position.x != previous_state.paddle_position.x {
+                narrow(&mut paddle_candidates, &changes, current_state.paddle_position.x);
+            }
+
+            if current_state.ball_position.x != previous_state.ball_position.x {
+                narrow(&mut ball_x_candidates, &changes, current_state.ball_position.x);
+            }
+
+            if current_state.ball_position.y != previous_state.ball_position.y {
+                narrow(&mut ball_y_candidates, &changes, current_state.ball_position.y);
+            }
+        }
+
+        let resolved = [&score_candidates, &paddle_candidates, &ball_x_candidates, &ball_y_candidates]
+            .iter()
+            .all(|x| x.as_ref().map(|set| set.len() == 1).unwrap_or(false));
+
+        if resolved || state == MachineState::Halted {
+            break;
+        }
+
+        let input = controller.next_input(&current_state);
+        game.machine.input_buffer.push_front(input);
+    }
+
+    Ok(MemoryLayout {
+        score: single_address("score", score_candidates)?,
+        paddle_x: single_address("paddle x", paddle_candidates)?,
+        paddle_row,
+        ball_x: single_address("ball x", ball_x_candidates)?,
+        ball_y: single_address("ball y", ball_y_candidates)?,
+        grid: grid.unwrap(),
+    })
+}
+
+pub fn patch(machine: &mut Machine, address: usize, value: i128) {
+    if address >= machine.memory.len() {
+        machine.memory.resize(address + 1, 0);
+    }
+
+    machine.memory[address] = value;
+}
+
+/// Turns the paddle's row into a solid row of paddle tiles between the side walls, so the ball
+/// bounces back no matter where it lands
+pub fn widen_paddle(machine: &mut Machine, layout: &MemoryLayout) {
+    let row_start = layout.grid.start + layout.paddle_row * layout.grid.width;
+    for x in 0..layout.grid.width {
+        let address = row_start + x;
+        if machine.memory[address] != WALL_TILE {
+            patch(machine, address, PADDLE_TILE);
+        }
+    }
+}
+
+fn narrow(candidates: &mut Option<HashSet<usize>>, changes: &[MemoryChange], value: i128) {
+    let matching: HashSet<usize> = changes.iter()
+        .filter(|x| x.after == value)
+        .map(|x| x.address)
+        .collect();
+
+    *candidates = match candidates.take() {
+        None => Some(matching),
+        Some(existing) => Some(existing.intersection(&matching).cloned().collect()),
+    };
+}
+
+fn single_address(name: &str, candidates: Option<HashSet<usize>>) -> Result<usize, String> {
+    let candidates = match candidates {
+        None => return Err(format!("The {} never changed", name)),
+        Some(x) => x,
+    };
+
+    if candidates.len() != 1 {
+        let mut addresses: Vec<usize> = candidates.into_iter().collect();
+        addresses.sort();
+        return Err(format!("Could not narrow down the {} address, candidates: {:?}", name, addresses));
+    }
+
+    Ok(candidates.into_iter().next().unwrap())
+}
+
+/// Searches memory for the screen contents.  The paddle and ball may be drawn from their own
+/// coordinates rather than the grid, so their cells match anything.
+fn find_grid(game: &Game) -> Result<GridLocation, String> {
+    let display = &game.display;
+    let memory = &game.machine.memory;
+    let size = display.width() * display.height();
+
+    if size == 0 || memory.len() < size {
+        return Err("Nothing has been drawn yet".to_owned());
+    }
+
+    for start in 0..=(memory.len() - size) {
+        let mut is_match = true;
+        for index in 0..size {
+            let expected = display.get(index % display.width(), index / display.width());
+            if expected == PADDLE_TILE || expected == BALL_TILE {
+                continue;
+            }
+
+            if memory[start + index] != expected {
+                is_match = false;
+                break;
+            }
+        }
+
+        if is_match {
+            return Ok(GridLocation {
+                start,
+                width: display.width(),
+                height: display.height(),
+            });
+        }
+    }
+
+    Err("The screen contents were not found in memory".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle_13::controllers::ScriptedController;
+    use crate::puzzle_13::{load_game, play};
+
+    #[test]
+    fn diff_lists_changed_addresses() {
+        let changes = diff_memory(&[1, 2, 3], &[1, 5, 3, 0, 7]);
+        assert_eq!(changes, vec![
+            MemoryChange { address: 1, before: 2, after: 5 },
+            MemoryChange { address: 4, before: 0, after: 7 },
+        ]);
+
+        assert_eq!(diff_memory(&[4, 9], &[4]), vec![MemoryChange { address: 1, before: 9, after: 0 }]);
+        assert_eq!(diff_memory(&[1, 2], &[1, 2]), vec![]);
+    }
+
+    #[test]
+    fn locates_game_state_in_memory() {
+        let machine = load_game();
+        let layout = locate_game_state(&machine).unwrap();
+        let grid_end = layout.grid.start + layout.grid.width * layout.grid.height;
+
+        let mut game = Game::new(Machine::new_from_memory(machine.memory.clone()));
+        game.next_frame();
+        let state = game.state();
+
+        assert_eq!(game.machine.memory[layout.paddle_x], state.paddle_position.x);
+        assert_eq!(game.machine.memory[layout.ball_x], state.ball_position.x);
+        assert_eq!(game.machine.memory[layout.ball_y], state.ball_position.y);
+        assert_eq!(layout.paddle_row as i128, state.paddle_position.y);
+        assert!(grid_end <= layout.score || layout.score < layout.grid.start);
+    }
+
+    #[test]
+    fn paddle_wall_wins_without_steering() {
+        let mut machine = load_game();
+        let layout = locate_game_state(&machine).unwrap();
+        widen_paddle(&mut machine, &layout);
+
+        let result = play(machine, &mut ScriptedController::new(Vec::new()), None);
+        assert!(result.is_won());
+        assert_eq!(result.score, 13989);
+    }
+}
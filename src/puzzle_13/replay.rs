@@ -0,0 +1,160 @@
+use super::{Viewer, SCORE_CHANNEL};
+use crate::display::{Display, Palette, Rgb, Update};
+use crate::gif::Animation;
+use crate::json;
+
+const TEXT_COLOR: Rgb = (255, 255, 255);
+const DIGIT_WIDTH: usize = 3;
+const DIGIT_HEIGHT: usize = 5;
+
+/// 3x5 pixel digits used to draw the score onto GIF frames, one bit mask per row
+const DIGITS: [[u8; DIGIT_HEIGHT]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// The screen as it looked at the end of a frame
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    pub tiles: Vec<i128>,
+    pub score: i128,
+}
+
+/// Captures every frame of a game so it can be exported once the game is over
+pub struct Recorder {
+    pub frames: Vec<Frame>,
+    palette: Option<Palette>,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Recorder {
+            frames: Vec::new(),
+            palette: None,
+        }
+    }
+
+    /// Exports the recording as an asciinema v2 cast, showing each frame for `frame_seconds`
+    pub fn to_asciicast(&self, frame_seconds: f64) -> Result<String, String> {
+        let palette = self.palette()?;
+        let (width, height) = self.size();
+        let mut result = format!("{{\"version\": 2, \"width\": {}, \"height\": {}, \"title\": \"Breakout\"}}\n",
+                                 width.max(20), height + 2);
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            let mut screen = String::from("\u{1b}[2J\u{1b}[H");
+            screen.push_str(&format!("Score: {}\r\n", frame.score));
+            for y in 0..frame.height {
+                for x in 0..frame.width {
+                    screen.push(palette.get(frame.tiles[y * frame.width + x]).symbol);
+                }
+
+                screen.push_str("\r\n");
+            }
+
+            let time = index as f64 * frame_seconds;
+            result.push_str(&format!("[{:.3}, \"o\", \"{}\"]\n", time, json::escape(&screen)));
+        }
+
+        Ok(result)
+    }
+
+    /// Exports the recording as a looping animated GIF with the score drawn above the screen.
+    /// Each tile is drawn as a `scale` x `scale` square and each frame is shown for `delay`
+    /// hundredths of a second.
+    pub fn to_gif(&self, scale: usize, delay: u16) -> Result<Vec<u8>, String> {
+        let palette = self.palette()?;
+        let (width, height) = self.size();
+        let header_height = DIGIT_HEIGHT + 2;
+        let image_width = width * scale;
+        let image_height = (height + header_height) * scale;
+
+        let mut colors = vec![palette.get(0).color, TEXT_COLOR];
+        for frame in &self.frames {
+            for tile in &frame.tiles {
+                let color = palette.get(*tile).color;
+                if !colors.contains(&color) {
+                    colors.push(color);
+                }
+            }
+        }
+
+        let mut animation = Animation::new(image_width, image_height, colors.clone(), delay);
+        for frame in &self.frames {
+            let mut pixels = vec![0; image_width * image_height];
+            let mut cells = vec![0; width * (height + header_height)];
+
+            for (index, digit) in frame.score.to_string().chars().enumerate() {
+                let glyph = match digit.to_digit(10) {
+                    None => continue, // negative sign
+                    Some(x) => DIGITS[x as usize],
+                };
+
+                for (row, mask) in glyph.iter().enumerate() {
+                    for column in 0..DIGIT_WIDTH {
+                        let x = 1 + index * (DIGIT_WIDTH + 1) + column;
+                        if x < width && mask & (1 << (DIGIT_WIDTH - 1 - column)) != 0 {
+                            cells[(row + 1) * width + x] = 1;
+                        }
+                    }
+                }
+            }
+
+            for y in 0..frame.height {
+                for x in 0..frame.width {
+                    let color = palette.get(frame.tiles[y * frame.width + x]).color;
+                    let index = colors.iter().position(|c| *c == color).unwrap();
+                    cells[(y + header_height) * width + x] = index as u8;
+                }
+            }
+
+            for y in 0..image_height {
+                for x in 0..image_width {
+                    pixels[y * image_width + x] = cells[(y / scale) * width + x / scale];
+                }
+            }
+
+            animation.add_frame(pixels);
+        }
+
+        Ok(animation.encode())
+    }
+
+    fn palette(&self) -> Result<&Palette, String> {
+        match &self.palette {
+            None => Err("No frames have been recorded".to_owned()),
+            Some(x) => Ok(x),
+        }
+    }
+
+    /// Largest screen size seen, since the screen grows while it's first drawn
+    fn size(&self) -> (usize, usize) {
+        let width = self.frames.iter().map(|x| x.width).max().unwrap_or(0);
+        let height = self.frames.iter().map(|x| x.height).max().unwrap_or(0);
+        (width, height)
+    }
+}
+
+impl Viewer for Recorder {
+    fn frame(&mut self, display: &Display, _updates: &[Update]) {
+        if self.palette.is_none() {
+            self.palette = Some(display.palette.clone());
+        }
+
+        self.frames.push(Frame {
+            width: display.width(),
+            height: display.height(),
+            tiles: display.pixels().to_vec(),
+            score: display.channel(SCORE_CHANNEL).unwrap_or(0),
+        });
+    }
+}
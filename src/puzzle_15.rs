@@ -1,25 +1,45 @@
-use crate::intcode::Machine;
-use std::collections::{HashMap, HashSet};
+use crate::geometry::{Direction, Point, SparseGrid};
+use crate::intcode::{parse_program, Machine};
+use crate::puzzle::Puzzle;
+use crate::search::bfs;
 
-#[derive(Debug)]
-enum Direction { North, South, East, West }
 enum CellContents { Empty, Wall, OxygenTank }
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-struct Position {
-    x: i32,
-    y: i32,
+type Position = Point<i32>;
+
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    type Input = Vec<i128>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    fn part1(program: &Vec<i128>) -> i32 {
+        let (map, tank_position) = explore(program);
+        steps_to_tank(&tank_position, &map)
+    }
+
+    fn part2(program: &Vec<i128>) -> i32 {
+        let (map, tank_position) = explore(program);
+        steps_to_fill(&tank_position, &map)
+    }
 }
 
-pub fn run() {
-    let mut machine = Machine::new_from_file("src/inputs/15A.txt");
-    let mut found_positions = HashMap::new();
-    let mut current_position = Position {x: 0, y: 0};
+/// Drives the repair droid through every open position, returning the map and where the
+/// oxygen tank is
+fn explore(program: &[i128]) -> (SparseGrid<i32, CellContents>, Position) {
+    let mut machine = Machine::new_from_memory(program.to_vec());
+    let mut found_positions = SparseGrid::new();
+    let mut current_position = Point::origin();
     let mut history = Vec::new();
     let mut tank_position = None;
 
     machine.run_program();
-    found_positions.insert(current_position.clone(), CellContents::Empty);
+    found_positions.insert(current_position, CellContents::Empty);
     loop {
         if let Some((next_direction, next_position)) = next_unexplored(&current_position, &found_positions) {
 
@@ -34,21 +54,21 @@ pub fn run() {
             match machine.output_buffer.pop_front().unwrap() {
                 0 => {
                     // wall, didn't move
-                    found_positions.insert(next_position.clone(), CellContents::Wall);
+                    found_positions.insert(next_position, CellContents::Wall);
                 }
 
                 1 => {
                     // Moved, no tank
-                    found_positions.insert(next_position.clone(), CellContents::Empty);
-                    history.push((next_direction, current_position.clone()));
+                    found_positions.insert(next_position, CellContents::Empty);
+                    history.push((next_direction, current_position));
                     current_position = next_position;
                 }
 
                 2 => {
                     // Moved, with tank
-                    found_positions.insert(next_position.clone(), CellContents::OxygenTank);
-                    history.push((next_direction, current_position.clone()));
-                    current_position = next_position.clone();
+                    found_positions.insert(next_position, CellContents::OxygenTank);
+                    history.push((next_direction, current_position));
+                    current_position = next_position;
                     tank_position = Some(next_position);
                 }
 
@@ -56,13 +76,13 @@ pub fn run() {
             }
         } else {
             // Dead end, so backtrack
-            if history.len() == 0 {
+            if history.is_empty() {
                 // Whole map has been mapped
                 break;
             }
 
             let (direction, position) = history.pop().unwrap();
-            let opposite_direction = opposite_direction(&direction);
+            let opposite_direction = direction.opposite();
 
             current_position = position;
             let input = direction_to_input(&opposite_direction);
@@ -80,42 +100,13 @@ pub fn run() {
         }
     }
 
-    let tank_at = tank_position.unwrap();
-    println!("Tank at {:?}", tank_at);
-
-    let steps_to_tank = steps_to_tank(&found_positions);
-    println!("Steps: {}", steps_to_tank);
-
-    let steps_to_fill = steps_to_fill(&tank_at, &found_positions);
-    println!("Filled in: {}", steps_to_fill);
+    (found_positions, tank_position.unwrap())
 }
 
-fn next_unexplored(current_position: &Position, found_positions: &HashMap<Position, CellContents>) -> Option<(Direction, Position)> {
-    let north = Position {x: current_position.x, y: current_position.y + 1};
-    let south = Position {x: current_position.x, y: current_position.y - 1};
-    let east = Position {x: current_position.x + 1, y: current_position.y};
-    let west = Position {x: current_position.x - 1, y: current_position.y};
-
-    if !found_positions.contains_key(&north) {
-        Some((Direction::North, north))
-    } else if !found_positions.contains_key(&east) {
-        Some((Direction::East, east))
-    } else if !found_positions.contains_key(&south) {
-        Some((Direction::South, south))
-    } else if !found_positions.contains_key(&west) {
-        Some((Direction::West, west))
-    } else {
-        None
-    }
-}
-
-fn opposite_direction(direction: &Direction) -> Direction {
-    match direction {
-        Direction::North => Direction::South,
-        Direction::South => Direction::North,
-        Direction::East => Direction::West,
-        Direction::West => Direction::East,
-    }
+fn next_unexplored(current_position: &Position, found_positions: &SparseGrid<i32, CellContents>) -> Option<(Direction, Position)> {
+    Direction::ALL.iter()
+        .map(|direction| (*direction, current_position.step(*direction)))
+        .find(|(_, position)| !found_positions.contains(position))
 }
 
 fn direction_to_input(direction: &Direction) -> i128 {
@@ -127,78 +118,22 @@ fn direction_to_input(direction: &Direction) -> i128 {
     }
 }
 
-fn steps_to_tank(map: &HashMap<Position, CellContents>) -> i32 {
-    let mut active_positions = vec![Position {x: 0, y: 0}];
-    let mut found_positions = HashSet::new();
-    let mut step_count = 0;
-    while active_positions.len() > 0 {
-        let mut new_positions = Vec::new();
-        for next_position in active_positions.drain(..) {
-            found_positions.insert(next_position.clone());
-
-            let north = Position {x: next_position.x, y: next_position.y + 1};
-            let south = Position {x: next_position.x, y: next_position.y - 1};
-            let east = Position {x: next_position.x + 1, y: next_position.y};
-            let west = Position {x: next_position.x - 1, y: next_position.y};
-
-            let positions_to_try = vec![north, south, east, west];
-            for try_position in positions_to_try {
-                match map.get(&try_position) {
-                    None => (),
-                    Some(x) => match x {
-                        CellContents::Empty => {
-                            if !found_positions.contains(&try_position) {
-                                new_positions.push(try_position.clone());
-                            }
-                        },
-                        CellContents::Wall => (),
-                        CellContents::OxygenTank => return step_count + 1,
-                    }
-                }
-            }
-        }
-
-        active_positions = new_positions;
-        step_count = step_count + 1;
-    }
-
-    unreachable!();
+/// Positions the droid can move to from a position
+fn open_neighbors(position: &Position, map: &SparseGrid<i32, CellContents>) -> Vec<Position> {
+    position.neighbors().into_iter()
+        .filter(|x| match map.get(x) {
+            Some(CellContents::Empty) | Some(CellContents::OxygenTank) => true,
+            Some(CellContents::Wall) | None => false,
+        })
+        .collect()
 }
 
-fn steps_to_fill(tank_position: &Position, map: &HashMap<Position, CellContents>) -> i32 {
-    let mut active_positions = vec![tank_position.clone()];
-    let mut found_positions = HashSet::new();
-    let mut step_count = 0;
-    while active_positions.len() > 0 {
-        let mut new_positions = Vec::new();
-        for next_position in active_positions.drain(..) {
-            found_positions.insert(next_position.clone());
-
-            let north = Position {x: next_position.x, y: next_position.y + 1};
-            let south = Position {x: next_position.x, y: next_position.y - 1};
-            let east = Position {x: next_position.x + 1, y: next_position.y};
-            let west = Position {x: next_position.x - 1, y: next_position.y};
-
-            let positions_to_try = vec![north, south, east, west];
-            for try_position in positions_to_try {
-                match map.get(&try_position) {
-                    None => (),
-                    Some(x) => match x {
-                        CellContents::Empty => {
-                            if !found_positions.contains(&try_position) {
-                                new_positions.push(try_position.clone());
-                            }
-                        },
-                        CellContents::Wall => (),
-                        CellContents::OxygenTank => (), // should never get hit
-                    }
-                }
-            }
-        }
-
-        active_positions = new_positions;
-        step_count = step_count + 1;
-    }
+fn steps_to_tank(tank_position: &Position, map: &SparseGrid<i32, CellContents>) -> i32 {
+    let distances = bfs(Point::origin(), |x| open_neighbors(x, map));
+    distances.get(tank_position).unwrap() as i32
+}
 
-    step_count - 1 // -1 because we used an extra step to notice that no more steps were needed
-}
\ No newline at end of file
+/// Minutes for oxygen to spread from the tank to every open position
+fn steps_to_fill(tank_position: &Position, map: &SparseGrid<i32, CellContents>) -> i32 {
+    bfs(*tank_position, |x| open_neighbors(x, map)).max() as i32
+}
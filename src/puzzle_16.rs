@@ -1,37 +1,67 @@
-use std::time::{SystemTime};
+use crate::puzzle::Puzzle;
 
-pub fn run() {
-    let input = get_input();
-    let offset = get_offset(&input) as usize;
-    println!("Offset: {}", offset);
-    println!("Starting message: {:?}", &input[offset..(offset + 8)]);
+const BASE_PATTERN: [i32; 4] = [0, 1, 0, -1];
 
-    let mut current = input;
-    for x in 0..100 {
-        let now = SystemTime::now();
-        let result = run_phase(&current, &offset);
-        current = result;
+pub struct Day16;
 
-        let elapsed = now.elapsed().unwrap();
-        println!("Phase {} completed ({}ms): {:?}", x, elapsed.as_millis(), &current[offset..(offset + 8)]);
+impl Puzzle for Day16 {
+    type Input = Vec<i32>;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn parse(input: &str) -> Vec<i32> {
+        input.trim().chars().map(|x| x.to_digit(10).unwrap() as i32).collect()
     }
 
-    println!("{:?}", &current[offset..(offset + 8)]);
-}
+    fn part1(input: &Vec<i32>) -> String {
+        let mut current = input.clone();
+        for _ in 0..100 {
+            current = run_full_phase(&current);
+        }
+
+        digits_to_string(&current[0..8])
+    }
+
+    fn part2(input: &Vec<i32>) -> String {
+        let mut repeated = Vec::with_capacity(input.len() * 10000);
+        for _ in 0..10000 {
+            repeated.extend_from_slice(input);
+        }
+
+        let offset = get_offset(&repeated) as usize;
+        if offset < repeated.len() / 2 {
+            panic!("Offset {} is in the first half of the signal, which the phase shortcut can't handle", offset);
+        }
 
-fn get_input() -> Vec<i32> {
-    let raw = "59791911701697178620772166487621926539855976237879300869872931303532122404711706813176657053802481833015214226705058704017099411284046473395211022546662450403964137283487707691563442026697656820695854453826690487611172860358286255850668069507687936410599520475680695180527327076479119764897119494161366645257480353063266653306023935874821274026377407051958316291995144593624792755553923648392169597897222058613725620920233283869036501950753970029182181770358827133737490530431859833065926816798051237510954742209939957376506364926219879150524606056996572743773912030397695613203835011524677640044237824961662635530619875905369208905866913334027160178";
-    //let raw = "03036732577212944063491565474664";
-    let mut repeated = String::new();
-    for _ in 0..10000 {
-        repeated.push_str(raw);
+        let mut current = repeated;
+        for _ in 0..100 {
+            current = run_phase(&current, &offset);
+        }
+
+        digits_to_string(&current[offset..(offset + 8)])
     }
-    repeated.chars().map(|x| x.to_digit(10).unwrap() as i32).collect()
 }
 
-fn run_phase(input: &Vec<i32>, offset: &usize) -> Vec<i32> {
+/// Applies the repeating pattern to every digit of the signal
+fn run_full_phase(input: &[i32]) -> Vec<i32> {
     let mut results = Vec::with_capacity(input.len());
-    results.resize(input.len(), 0);
+    for y in 0..input.len() {
+        let mut sum = 0;
+        for (x, digit) in input.iter().enumerate().skip(y) {
+            let pattern_index = ((x + 1) / (y + 1)) % BASE_PATTERN.len();
+            sum += digit * BASE_PATTERN[pattern_index];
+        }
+
+        results.push(sum.abs() % 10);
+    }
+
+    results
+}
+
+/// In the second half of the signal every digit is just the sum of itself and the digits after
+/// it, so only the digits from the offset onwards are calculated
+fn run_phase(input: &[i32], offset: &usize) -> Vec<i32> {
+    let mut results = vec![0; input.len()];
 
     let mut last_sum = 0;
     for y in (*offset..input.len()).rev() {
@@ -43,12 +73,32 @@ fn run_phase(input: &Vec<i32>, offset: &usize) -> Vec<i32> {
 
     results
 }
-fn get_offset(input: &Vec<i32>) -> i32 {
+
+fn get_offset(input: &[i32]) -> i32 {
     let mut value = String::new();
-    for x in 0..7 {
-        let digit = input[x];
+    for digit in &input[..7] {
         value.push_str(digit.to_string().as_str());
     }
 
     value.parse().unwrap()
-}
\ No newline at end of file
+}
+
+fn digits_to_string(digits: &[i32]) -> String {
+    digits.iter().map(|x| x.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_1_examples() {
+        assert_eq!(Day16::part1(&Day16::parse("80871224585914546619083218645595")), "24176176");
+        assert_eq!(Day16::part1(&Day16::parse("19617804207202209144916044189917")), "73745418");
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(Day16::part2(&Day16::parse("03036732577212944063491565474664")), "84462026");
+    }
+}
@@ -1,23 +1,24 @@
-use crate::intcode::Machine;
+use crate::display::Rgb;
+use crate::geometry::{Direction, Grid, Point, Turn};
+use crate::image::Image;
+use crate::intcode::{parse_program, Machine, MachineState};
+use crate::puzzle::Puzzle;
 use std::char;
 use std::collections::HashSet;
 
-#[derive(Clone)]
-enum FaceDirection { North, South, East, West }
+const FUNCTION_NAMES: [char; 3] = ['A', 'B', 'C'];
+const MAX_LINE_LENGTH: usize = 20;
 
-#[derive(Eq, PartialEq, Debug)]
-enum TurnDirection { Left, Right }
+const OPEN_SPACE_COLOR: Rgb = (20, 20, 30);
+const SCAFFOLD_COLOR: Rgb = (200, 200, 200);
+const ROBOT_COLOR: Rgb = (230, 60, 60);
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct Position {
-    x: u32,
-    y: u32,
-}
+type Position = Point<u32>;
 
 #[derive(Clone)]
 struct Robot {
     position: Position,
-    facing: FaceDirection,
+    facing: Direction,
 }
 
 struct Map {
@@ -29,34 +30,154 @@ struct Map {
 
 #[derive(Eq, PartialEq, Debug)]
 struct Command {
-    direction: TurnDirection,
+    direction: Turn,
     units: u32,
 }
 
-pub fn run() {
-    let mut machine = Machine::new_from_file("src/inputs/17.txt");
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    type Input = Vec<i128>;
+    type Part1 = u32;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
 
-    //machine.memory[0] = 2; // allow input
+    /// Sum of the alignment parameters of every scaffold intersection
+    fn part1(program: &Vec<i128>) -> u32 {
+        let mut machine = Machine::new_from_memory(program.clone());
+        machine.run_program();
+        let map = get_map(&mut machine);
+
+        get_intersections(&map.scaffolding).iter()
+            .map(|x| x.x * x.y)
+            .sum()
+    }
+
+    /// Amount of dust collected after walking the whole scaffold
+    fn part2(program: &Vec<i128>) -> i128 {
+        let mut machine = Machine::new_from_memory(program.clone());
+        machine.run_program();
+        let map = get_map(&mut machine);
+        let route = get_route(&map).iter().map(command_to_string).collect::<Vec<String>>();
+
+        let (main_routine, functions) = match compress_route(&route) {
+            None => panic!("Route could not be split into movement functions: {}", route.join(",")),
+            Some(x) => x,
+        };
+
+        let mut machine = Machine::new_from_memory(program.clone());
+        machine.memory[0] = 2; // allow input
+        let mut lines = vec![main_routine];
+        lines.extend(functions);
+        lines.push("n".to_owned()); // no video feed
+
+        for line in lines {
+            for ch in line.chars() {
+                machine.input_buffer.push_back(ch as i128);
+            }
+
+            machine.input_buffer.push_back('\n' as i128);
+        }
+
+        let state = machine.run_program();
+        if state != MachineState::Halted {
+            panic!("Robot is still waiting for input");
+        }
+
+        *machine.output_buffer.back().unwrap()
+    }
+}
+
+/// The camera view of the scaffolding, with the vacuum robot highlighted
+pub fn render_image(input: &str) -> Image {
+    let mut machine = Machine::new_from_memory(Day17::parse(input));
     machine.run_program();
     let map = get_map(&mut machine);
-    let route = get_route(&map);
 
-    let mut raw_commands = String::new();
-    for x in 0..route.len() {
-        if x > 0 {
-            raw_commands.push(',');
+    let mut grid = Grid::new(map.width as usize, map.height as usize, OPEN_SPACE_COLOR);
+    for position in &map.scaffolding {
+        grid.set(&Point::new(position.x as usize, position.y as usize), SCAFFOLD_COLOR);
+    }
+
+    let robot = &map.robot.position;
+    grid.set(&Point::new(robot.x as usize, robot.y as usize), ROBOT_COLOR);
+    Image::from_grid(&grid, |color| *color)
+}
+
+fn command_to_string(command: &Command) -> String {
+    let direction = match command.direction {
+        Turn::Right => 'R',
+        Turn::Left => 'L',
+    };
+
+    format!("{},{}", direction, command.units)
+}
+
+/// Splits the route into a main routine calling up to 3 movement functions, with every line
+/// being at most 20 characters
+fn compress_route(route: &[String]) -> Option<(String, Vec<String>)> {
+    let mut functions = Vec::new();
+    let mut main_routine = Vec::new();
+    if !assign_functions(route, &mut functions, &mut main_routine) {
+        return None;
+    }
+
+    let names = main_routine.iter()
+        .map(|x| FUNCTION_NAMES[*x].to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+
+    if names.len() > MAX_LINE_LENGTH {
+        return None;
+    }
+
+    Some((names, functions.iter().map(|x| x.join(",")).collect()))
+}
+
+fn assign_functions(route: &[String], functions: &mut Vec<Vec<String>>, main_routine: &mut Vec<usize>) -> bool {
+    if route.is_empty() {
+        return true;
+    }
+
+    if main_routine.len() * 2 > MAX_LINE_LENGTH {
+        return false;
+    }
+
+    for x in 0..functions.len() {
+        if route.starts_with(&functions[x]) {
+            main_routine.push(x);
+            if assign_functions(&route[functions[x].len()..], functions, main_routine) {
+                return true;
+            }
+
+            main_routine.pop();
+        }
+    }
+
+    if functions.len() == FUNCTION_NAMES.len() {
+        return false;
+    }
+
+    for length in 1..=route.len() {
+        let function = route[0..length].to_vec();
+        if function.join(",").len() > MAX_LINE_LENGTH {
+            break;
         }
 
-        match route[x].direction {
-            TurnDirection::Right => raw_commands.push('R'),
-            TurnDirection::Left => raw_commands.push('L'),
+        functions.push(function);
+        main_routine.push(functions.len() - 1);
+        if assign_functions(&route[length..], functions, main_routine) {
+            return true;
         }
 
-        raw_commands.push(',');
-        raw_commands.push_str(&route[x].units.to_string());
+        main_routine.pop();
+        functions.pop();
     }
 
-    println!("Commands: {}", raw_commands);
+    false
 }
 
 fn get_map(machine: &mut Machine) -> Map {
@@ -68,35 +189,34 @@ fn get_map(machine: &mut Machine) -> Map {
     for output in machine.output_buffer.drain(..) {
         let ch = char::from_u32(output as u32).unwrap();
         match &ch {
-            '.' => from_left = from_left + 1,
+            '.' => from_left += 1,
             '#' => {
-                blocks.insert(Position {x: from_left, y: from_top});
-                from_left = from_left + 1;
+                blocks.insert(Point::new(from_left, from_top));
+                from_left += 1;
             }
 
+            // The output ends with a blank line, which isn't part of the map
+            '\n' if from_left == 0 => (),
             '\n' => {
-                from_top = from_top + 1;
+                from_top += 1;
                 width = from_left;
                 from_left = 0;
             }
 
             '^' => {
                 robot = Some(Robot {
-                    position: Position { x: from_left, y: from_top },
-                    facing: FaceDirection::North,
+                    position: Point::new(from_left, from_top),
+                    facing: Direction::North,
                 });
 
-                from_left = from_left + 1;
+                from_left += 1;
             }
 
-            _ => from_left = from_left + 1,
+            _ => from_left += 1,
         }
-
-        print!("{}", ch);
     }
 
     let height = from_top;
-    println!();
 
     Map { height, width, scaffolding: blocks, robot: robot.unwrap() }
 }
@@ -112,20 +232,10 @@ fn get_intersections(blocks: &HashSet<Position>) -> Vec<Position> {
     let mut results = Vec::new();
 
     for block in blocks {
-        if block.x == 0 || block.y == 0 {
-            continue;
-        }
-
-        let north = Position {x: block.x, y: block.y - 1};
-        let south = Position {x: block.x, y: block.y + 1};
-        let east = Position {x: block.x + 1, y: block.y};
-        let west = Position {x: block.x - 1, y: block.y};
-
-        if blocks.contains(&north) &&
-            blocks.contains(&south) &&
-            blocks.contains(&east) &&
-            blocks.contains(&west) {
-            results.push(block.clone());
+        // Blocks on the top or left edge have fewer than 4 neighbors, so can't be intersections
+        let neighbors = block.neighbors();
+        if neighbors.len() == 4 && neighbors.iter().all(|x| blocks.contains(x)) {
+            results.push(*block);
         }
     }
 
@@ -153,103 +263,29 @@ fn get_route(map: &Map) -> Vec<Command> {
     commands
 }
 
-fn get_turn_direction(map: &Map, robot: &Robot) -> Option<(TurnDirection, FaceDirection)> {
-    let north = get_north(&robot.position);
-    let south  = get_south(&robot.position);
-    let east = get_east(&robot.position);
-    let west = get_west(&robot.position);
-
-    match robot.facing {
-        FaceDirection::North => {
-            if east.is_some() && map.scaffolding.contains(&east.unwrap()) {
-                Some((TurnDirection::Right, FaceDirection::East))
-            } else if west.is_some() && map.scaffolding.contains(&west.unwrap()) {
-                Some((TurnDirection::Left, FaceDirection::West))
-            } else {
-                None
-            }
-        }
-
-        FaceDirection::South => {
-            if east.is_some() && map.scaffolding.contains(&east.unwrap()) {
-                Some((TurnDirection::Left, FaceDirection::East))
-            } else if west.is_some() && map.scaffolding.contains(&west.unwrap()) {
-                Some((TurnDirection::Right, FaceDirection::West))
-            } else {
-                None
-            }
-        }
-
-        FaceDirection::East => {
-            if north.is_some() && map.scaffolding.contains(&north.unwrap()) {
-                Some((TurnDirection::Left, FaceDirection::North))
-            } else if south.is_some() && map.scaffolding.contains(&south.unwrap()) {
-                Some((TurnDirection::Right, FaceDirection::South))
-            } else {
-                None
-            }
-        }
-
-        FaceDirection::West => {
-            if north.is_some() && map.scaffolding.contains(&north.unwrap()) {
-                Some((TurnDirection::Left, FaceDirection::North))
-            } else if south.is_some() && map.scaffolding.contains(&south.unwrap()) {
-                Some((TurnDirection::Right, FaceDirection::South))
-            } else {
-                None
+fn get_turn_direction(map: &Map, robot: &Robot) -> Option<(Turn, Direction)> {
+    for turn in &[Turn::Right, Turn::Left] {
+        let facing = robot.facing.turn(*turn);
+        if let Some(position) = robot.position.checked_step(facing) {
+            if map.scaffolding.contains(&position) {
+                return Some((*turn, facing));
             }
         }
     }
+
+    None
 }
 
 fn move_robot(map: &Map, robot: &mut Robot) -> u32 {
     let mut steps = 0;
-    loop {
-        let next_position = match robot.facing {
-            FaceDirection::North => get_north(&robot.position),
-            FaceDirection::South => get_south(&robot.position),
-            FaceDirection::East => get_east(&robot.position),
-            FaceDirection::West => get_west(&robot.position),
-        };
-
-        if next_position.is_none() {
+    while let Some(position) = robot.position.checked_step(robot.facing) {
+        if !map.scaffolding.contains(&position) {
             break;
         }
 
-        let position = next_position.unwrap();
-        if map.scaffolding.contains(&position) {
-            robot.position = position;
-        } else {
-            break;
-        }
-
-        steps = steps + 1;
+        robot.position = position;
+        steps += 1;
     }
 
     steps
 }
-
-fn get_north(position: &Position) -> Option<Position> {
-    if position.y == 0 {
-        None
-    } else {
-        Some(Position { x: position.x, y: position.y - 1 })
-    }
-}
-
-fn get_south(position: &Position) -> Option<Position> {
-    Some(Position { x: position.x, y: position.y + 1 })
-}
-
-fn get_east(position: &Position) -> Option<Position> {
-    Some(Position { x: position.x + 1, y: position.y })
-}
-
-fn get_west(position: &Position) -> Option<Position> {
-    if position.x == 0 {
-        None
-    } else {
-        Some(Position { x: position.x - 1, y: position.y })
-    }
-}
-
@@ -1,12 +1,11 @@
+use crate::geometry::{Direction, Point};
+use crate::puzzle::{self, Puzzle};
+use crate::search::{bfs, dijkstra};
 use std::collections::{HashSet, HashMap};
 
-#[derive(Eq, PartialEq, Hash, Clone, Debug)]
-struct Position {
-    x: i32,
-    y: i32,
-}
+type Position = Point<i32>;
 
-struct Map {
+pub struct Map {
     walkable: HashSet<Position>,
     doors: HashMap<Position, char>,
     keys: HashMap<Position, char>,
@@ -15,14 +14,6 @@ struct Map {
     start_at: Position,
 }
 
-#[derive(Clone)]
-struct Instance {
-    current: Position,
-    //previous: HashSet<Position>,
-    previous: Option<Position>,
-    collected_keys: HashSet<char>,
-}
-
 #[derive(Debug)]
 struct Edge {
     length: i32,
@@ -31,300 +22,229 @@ struct Edge {
     keys_in_the_way: Vec<char>,
 }
 
-pub fn run() {
-    let map = read_map();
-    let steps = calculate_steps_to_all_keys(&map);
-    println!("Completed in {:?} steps", steps);
-}
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    type Input = Map;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Map {
+        read_map(input)
+    }
+
+    fn part1(map: &Map) -> i32 {
+        calculate_steps_to_all_keys(map)
+    }
 
-fn read_map() -> Map {
-    let input = "#################
-#i.G..c...e..H.p#
-########.########
-#j.A..b...f..D.o#
-########@########
-#k.E..a...g..B.n#
-########.########
-#l.F..d...h..C.m#
-#################".to_owned();
+    /// Splits the vault into four with a robot in each.  Maps whose start isn't in an open area,
+    /// like the part 1 examples, can't be split.
+    fn part2(map: &Map) -> i32 {
+        let starts = match split_vault(map) {
+            None => puzzle::not_applicable("The area around the start is not open, so the vault can't be split"),
+            Some(x) => x,
+        };
+
+        match steps_with_robots(map, &starts) {
+            None => panic!("No path collects every key"),
+            Some(steps) => steps,
+        }
+    }
+}
 
+fn read_map(input: &str) -> Map {
     let mut map = Map {
         walkable: HashSet::new(),
         doors: HashMap::new(),
         keys: HashMap::new(),
         max_x: 0,
         max_y: 0,
-        start_at: Position {x: 0, y: 0},
+        start_at: Point::origin(),
     };
 
-    let mut y = 0;
-    for line in input.lines() {
-       let mut x = 0;
-        for ch in line.chars() {
+    for (y, line) in input.lines().enumerate() {
+        let y = y as i32;
+        for (x, ch) in line.chars().enumerate() {
+            let x = x as i32;
             if ch != '#' { // ignore walls
-                let position = Position { x, y };
-                map.walkable.insert(position.clone());
+                let position = Point::new(x, y);
+                map.walkable.insert(position);
 
                 if ch == '@' {
                     map.start_at = position;
                 } else if ch.is_alphabetic() && ch.is_uppercase() {
-                    map.doors.insert(position, ch.clone());
+                    map.doors.insert(position, ch);
                 } else if ch.is_alphabetic() && ch.is_lowercase() {
-                    map.keys.insert(position, ch.clone());
+                    map.keys.insert(position, ch);
                 }
             }
 
             if map.max_x < x {
                 map.max_x = x;
             }
-
-            x = x + 1;
         }
 
         if map.max_y < y {
             map.max_y = y;
         }
-
-        y = y + 1;
     }
 
     map
 }
 
+/// Shortest path from the start to every reachable key, along with the doors and other keys
+/// along the way
 fn get_edges_to_keys(start_position: &Position, map: &Map) -> Vec<Edge> {
-    #[derive(Clone)]
-    struct Agent {
-        current: Position,
-        doors_encountered: HashSet<char>,
-        keys_encountered: Vec<char>,
-    }
-
-    let mut edges = Vec::new();
-    let mut steps = 0;
-    let mut visited = HashSet::new();
-    let mut agents = Vec::new();
-    agents.push(Agent {
-        current: start_position.clone(),
-        doors_encountered: HashSet::new(),
-        keys_encountered: Vec::new(),
+    let distances = bfs(*start_position, |position| {
+        position.neighbors().into_iter()
+            .filter(|x| map.walkable.contains(x))
+            .collect::<Vec<Position>>()
     });
 
-    loop {
-        steps = steps + 1;
-
-        if agents.len() == 0 {
-            break;
+    let mut edges = Vec::new();
+    for (position, key) in &map.keys {
+        if position == start_position {
+            continue;
         }
 
-        let mut step_agents = Vec::new();
-        for agent in agents {
-            let candidates = vec![
-                get_north(&agent.current),
-                get_south(&agent.current),
-                get_east(&agent.current),
-                get_west(&agent.current),
-            ];
-
-            for candidate in candidates {
-                if visited.contains(&candidate) {
-                    continue;
-                }
-
-                if !map.walkable.contains(&candidate) {
-                    continue;
-                }
-
-                let mut new_agent = agent.clone();
-                if let Some(door) = map.doors.get(&candidate) {
-                    new_agent.doors_encountered.insert(door.clone());
-                }
+        let path = match distances.path_to(position) {
+            None => continue, // Unreachable from here
+            Some(x) => x,
+        };
+
+        let in_between = &path[1..path.len() - 1];
+        edges.push(Edge {
+            length: (path.len() - 1) as i32,
+            target: *key,
+            doors: in_between.iter().filter_map(|x| map.doors.get(x)).cloned().collect(),
+            keys_in_the_way: in_between.iter().filter_map(|x| map.keys.get(x)).cloned().collect(),
+        });
+    }
 
-                if let Some(key) = map.keys.get(&candidate) {
-                    edges.push(Edge {
-                        length: steps,
-                        doors: agent.doors_encountered.clone(),
-                        keys_in_the_way: agent.keys_encountered.clone(),
-                        target: key.clone(),
-                    });
+    edges
+}
 
-                    new_agent.keys_encountered.push(key.clone());
-                }
+fn calculate_steps_to_all_keys(map: &Map) -> i32 {
+    steps_to_collect_keys(map, &[map.start_at]).unwrap()
+}
 
-                visited.insert(candidate.clone());
-                new_agent.current = candidate.clone();
-                step_agents.push(new_agent);
+/// Returns the four diagonal positions the robots start from, as long as the start and every
+/// position around it is open
+fn split_vault(map: &Map) -> Option<Vec<Position>> {
+    let center = &map.start_at;
+    for y in (center.y - 1)..=(center.y + 1) {
+        for x in (center.x - 1)..=(center.x + 1) {
+            if !map.walkable.contains(&Point::new(x, y)) {
+                return None;
             }
         }
-
-        agents = step_agents;
     }
 
-    edges
+    Some(vec![
+        center.step(Direction::North).step(Direction::West),
+        center.step(Direction::North).step(Direction::East),
+        center.step(Direction::South).step(Direction::West),
+        center.step(Direction::South).step(Direction::East),
+    ])
 }
 
-fn calculate_steps_to_all_keys(map: &Map) -> i32 {
-    #[derive(Clone)]
-    struct Agent {
-        steps_taken: i32,
-        current_key: char,
-        keys_collected: HashSet<char>,
-        keys_in_order: Vec<char>,
-    }
+/// Fewest steps for robots at each of the starting positions to collect every key, with the
+/// cells next to the original start treated as walls
+fn steps_with_robots(map: &Map, starts: &[Position]) -> Option<i32> {
+    let center = &map.start_at;
+    let mut split_map = Map {
+        walkable: map.walkable.clone(),
+        doors: map.doors.clone(),
+        keys: map.keys.clone(),
+        max_x: map.max_x,
+        max_y: map.max_y,
+        start_at: *center,
+    };
 
-    #[derive(Hash, Eq, PartialEq)]
-    struct CacheItem {
-        keys_in_alpha_order: Vec<char>,
-        last_key: char,
+    split_map.walkable.remove(center);
+    for position in center.neighbors() {
+        split_map.walkable.remove(&position);
     }
 
+    steps_to_collect_keys(&split_map, starts)
+}
+
+/// Fewest steps for robots at each of the starting positions to collect every key, moving one
+/// robot at a time.  Searches over which key each robot stands on and which keys are collected.
+fn steps_to_collect_keys(map: &Map, starts: &[Position]) -> Option<i32> {
+    // Robots start on labels '0', '1', ..., so they can share the edge lookup with the keys
     let mut key_edges = HashMap::new();
-    key_edges.insert('@', get_edges_to_keys(&map.start_at, &map));
-    for (position, key) in &map.keys {
-        key_edges.insert(key.clone(), get_edges_to_keys(&position, &map));
+    let mut robot_labels = Vec::new();
+    for (x, start) in starts.iter().enumerate() {
+        let label = std::char::from_digit(x as u32, 10).unwrap();
+        key_edges.insert(label, get_edges_to_keys(start, map));
+        robot_labels.push(label);
     }
 
-    let mut active_agents = Vec::new();
-    active_agents.push(Agent {
-        current_key: '@',
-        keys_collected: HashSet::new(),
-        steps_taken: 0,
-        keys_in_order: Vec::new(),
-    });
-
-    let mut length_cache = HashMap::new();
-    let mut finished_agent: Option<Agent> = None;
-    loop {
-        if active_agents.len() == 0 {
-            break;
-        }
-
-        let _start_count = active_agents.len();
-        let agent = active_agents.remove(0);
-        //println!("Agent starting at {} at {} steps ({:?})", agent.current_key, agent.steps_taken, agent.keys_in_order);
-
-        let edges = &key_edges[&agent.current_key];
-        for edge_index in 0..edges.len() {
-            let edge = edges.get(edge_index).unwrap();
-            if agent.keys_collected.contains(&edge.target) {
-                continue; // We've already collected this key
-            }
+    for (position, key) in &map.keys {
+        key_edges.insert(*key, get_edges_to_keys(position, map));
+    }
 
-            let mut has_all_required_keys = true;
-            for door in &edge.doors {
-                if !agent.keys_collected.contains(&door.to_ascii_lowercase()) {
-                    has_all_required_keys = false;
-                    break;
+    let all_keys = map.keys.values().fold(0u32, |mask, key| mask | key_bit(*key));
+    let next_states = |(robots, collected): &(Vec<char>, u32)| {
+        let mut states = Vec::new();
+        for robot in 0..robots.len() {
+            for edge in &key_edges[&robots[robot]] {
+                if collected & key_bit(edge.target) != 0 {
+                    continue; // Already collected
                 }
-            }
 
-            if !has_all_required_keys {
-                continue; // We can't traverse this edge yet until we get more keys
-            }
-
-            // Make sure this edge isn't better handled by another edge (e.g. other edge picks this
-            // edge's target up in it's path)
-            let mut ignore_this_edge = false;
-            for other_edge_index in 0..edges.len() {
-                if other_edge_index == edge_index {
-                    continue;
+                if edge.doors.iter().any(|door| collected & key_bit(door.to_ascii_lowercase()) == 0) {
+                    continue; // Missing a key for a door on the way
                 }
 
-                let other_edge = edges.get(other_edge_index).unwrap();
-                if other_edge.keys_in_the_way.contains(&edge.target) {
-                    let mut has_all_required_keys = true;
-                    for door in &other_edge.doors {
-                        if !agent.keys_collected.contains(&door.to_ascii_lowercase()) {
-                            has_all_required_keys = false;
-                            break;
-                        }
-                    }
-
-                    if has_all_required_keys {
-                        ignore_this_edge = true;
-                        break;
-                    }
+                let mut new_collected = collected | key_bit(edge.target);
+                for middle_key in &edge.keys_in_the_way {
+                    new_collected |= key_bit(*middle_key);
                 }
-            }
 
-            if ignore_this_edge {
-                continue; // Since this edge is better taken care by another edge, ignore this one
+                let mut new_robots = robots.clone();
+                new_robots[robot] = edge.target;
+                states.push(((new_robots, new_collected), edge.length as usize));
             }
+        }
 
-            let mut new_agent = agent.clone();
-            new_agent.steps_taken = agent.steps_taken + edge.length;
-
-            for middle_key in &edge.keys_in_the_way {
-                if !new_agent.keys_collected.contains(middle_key) {
-                    new_agent.keys_collected.insert(middle_key.clone());
-                    new_agent.keys_in_order.push(middle_key.clone());
-                }
-            }
-
-            new_agent.keys_collected.insert(edge.target.clone());
-            new_agent.keys_in_order.push(edge.target.clone());
-            new_agent.current_key = edge.target.clone();
-
-            //println!("Taking {} steps to {} ({} total)", edge.length, edge.target, new_agent.steps_taken);
-            if finished_agent.is_some() && finished_agent.as_ref().unwrap().steps_taken <= new_agent.steps_taken {
-                // An agent has finished and has less number of steps than this current agent.
-                // Therefore there's no point in going forward.
-                //println!("At more steps than finished agent, ignoring this agent");
-                continue;
-            }
+        states
+    };
 
-            let mut keys_in_alpha_order: Vec<char> = new_agent.keys_collected
-                .iter()
-                .map(|x| x.clone())
-                .collect();
-
-            keys_in_alpha_order.sort();
-
-            let cache_key = CacheItem {
-                keys_in_alpha_order,
-                last_key: edge.target.clone(),
-            };
-
-            match length_cache.get(&cache_key) {
-                None => {length_cache.insert(cache_key, new_agent.steps_taken);},
-                Some(length) => {
-                    if *length < new_agent.steps_taken {
-                        continue; // We already have a shorter path for this segment
-                    } else {
-                        length_cache.insert(cache_key, new_agent.steps_taken);
-                    }
-                }
-            }
+    dijkstra((robot_labels, 0u32), next_states, |(_, collected)| *collected == all_keys)
+        .map(|path| path.cost as i32)
+}
 
-            if new_agent.keys_collected.len() == map.keys.len() {
-                println!("Agent finished {}: {:?} ({} agents remaining)", new_agent.steps_taken, new_agent.keys_in_order, active_agents.len());
-                finished_agent = Some(new_agent);
-            } else {
-                let mut add_at = None;
-                for x in 0..active_agents.len() {
-                    if active_agents[x].steps_taken >= new_agent.steps_taken {
-                        add_at = Some(x);
-                        break;
-                    }
-                }
+fn key_bit(key: char) -> u32 {
+    1 << (key as u32 - 'a' as u32)
+}
 
-                if let Some(x) = add_at {
-                    active_agents.insert(x, new_agent);
-                } else {
-                    active_agents.push(new_agent);
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_robots_collect_every_key() {
+        let map = read_map("#######
+#a.#Cd#
+##...##
+##.@.##
+##...##
+#cB#Ab#
+#######");
+
+        assert_eq!(Day18::part2(&map), 8);
+    }
 
-        //println!("Agent count went from {} to {}", _start_count, active_agents.len());
+    #[test]
+    fn closed_start_cannot_be_split() {
+        let solution = puzzle::solve::<Day18>("#########
+#b.A.@.a#
+#########", &[1, 2]);
 
-        let mut _input = String::new();
-        //io::stdin().read_line(&mut _input).unwrap();
+        assert_eq!(solution.answers[0].answer, Ok("8".to_owned()));
+        assert_eq!(solution.answers[1].answer,
+                   Err(puzzle::PartError::NotApplicable("The area around the start is not open, so the vault can't be split".to_owned())));
     }
-
-    finished_agent.unwrap().steps_taken
 }
-
-fn get_north(current: &Position) -> Position {Position {x: current.x, y: current.y - 1}}
-fn get_south(current: &Position) -> Position {Position {x: current.x, y: current.y + 1}}
-fn get_east(current: &Position) -> Position {Position {x: current.x + 1, y: current.y}}
-fn get_west(current: &Position) -> Position {Position {x: current.x - 1, y: current.y}}
\ No newline at end of file
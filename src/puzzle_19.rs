@@ -1,35 +1,68 @@
-use crate::intcode::{Machine};
+use crate::display::Rgb;
+use crate::geometry::{Grid, Point};
+use crate::image::Image;
+use crate::intcode::{parse_program, Machine};
+use crate::puzzle::Puzzle;
 use std::collections::{HashMap};
-use std::i128;
 
 const BOX_SIZE: i128 = 100;
 
-#[derive(Clone, Hash, Eq, PartialEq, Debug)]
-struct Position {
-    x: i128,
-    y: i128,
-}
+/// How much of the beam is shown past each edge of the box when rendering it
+const MARGIN: i128 = 10;
+
+const EMPTY_COLOR: Rgb = (20, 20, 30);
+const BEAM_COLOR: Rgb = (90, 140, 220);
+const BOX_COLOR: Rgb = (240, 200, 60);
 
 struct RowData {
     width: i128,
     start_x: i128,
 }
 
-pub fn run() {
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    type Input = Vec<i128>;
+    type Part1 = usize;
+    type Part2 = i128;
+
+    fn parse(input: &str) -> Vec<i128> {
+        parse_program(input)
+    }
+
+    /// Number of points affected by the beam in the 50x50 area closest to the emitter
+    fn part1(program: &Vec<i128>) -> usize {
+        let mut count = 0;
+        for y in 0..50 {
+            for x in 0..50 {
+                if is_in_beam(program, x, y) {
+                    count += 1;
+                }
+            }
+        }
+
+        count
+    }
+
+    fn part2(program: &Vec<i128>) -> i128 {
+        let box_pos = find_box(program);
+        box_pos.x * 10000 + box_pos.y
+    }
+}
+
+/// Finds the top left corner of the closest square that fits entirely in the beam
+fn find_box(program: &[i128]) -> Point<i128> {
     let mut rows = HashMap::new();
-    let mut beam_count = 0;
 
     let mut min_x = 0;
     let mut y = 0;
     let mut box_pos = None;
     while box_pos.is_none() {
-        let row = get_data_for_row(y, min_x);
-        beam_count = beam_count + row.width;
+        let row = get_data_for_row(program, y, min_x);
         min_x = row.start_x;
-        println!("Row {} width: {}", y, row.width);
 
         if row.width < BOX_SIZE + 50 {
-            y = y + 50;
+            y += 50;
             continue;
         }
 
@@ -39,50 +72,70 @@ pub fn run() {
             Some(pos) => { box_pos = Some(pos); }
         }
 
-        y = y + 1;
+        y += 1;
     }
 
-    println!("{} beam positions", beam_count);
+    box_pos.unwrap()
+}
 
-    let final_box_pos = box_pos.unwrap();
+fn is_in_beam(program: &[i128], x: i128, y: i128) -> bool {
+    let mut machine = Machine::new_from_memory(program.to_vec());
+    machine.input_buffer.push_back(x);
+    machine.input_buffer.push_back(y);
+    machine.run_program();
+
+    match machine.output_buffer.pop_front() {
+        None => panic!("Position ({}, {}) did not return any output value", x, y),
+        Some(val) => match val {
+            0 => false,
+            1 => true,
+            v => panic!("Output of {} received", v),
+        }
+    }
+}
 
-    println!("Position: {:?}", final_box_pos);
-    println!("Answer: {}", final_box_pos.x * 10000 + final_box_pos.y);
+/// The beam around the closest square that fits in it, with the square highlighted
+pub fn render_image(input: &str) -> Image {
+    let program = Day19::parse(input);
+    let box_pos = find_box(&program);
+    Image::from_grid(&render_area(&program, &box_pos), |color| *color)
 }
 
-fn render_area(rows: &HashMap<i128, RowData>, start_y: i128, end_y: i128) {
-    let mut y = start_y - 1;
-    let _first_row = rows.get(&start_y).unwrap();
-    let last_row = rows.get(&end_y).unwrap();
-    while y <= end_y {
-        print!("{}: ", y);
-        for x in 0..(last_row.width + last_row.start_x) {
-            let mut machine = Machine::new_from_file("src/inputs/19.txt");
-            machine.input_buffer.push_back(x);
-            machine.input_buffer.push_back(y);
-            machine.run_program();
-
-            match machine.output_buffer.pop_front() {
-                None => panic!("Position ({}, {}) did not return any output value", x, y),
-                Some(val) => match val {
-                    0 => print!("."),
-                    1 => print!("#"),
-                    v => panic!("Output of {} received", v),
-                }
+/// Draws the beam in the area around the box, `MARGIN` points past each of its edges
+fn render_area(program: &[i128], box_pos: &Point<i128>) -> Grid<Rgb> {
+    let top = box_pos.y - MARGIN;
+    let left = box_pos.x - MARGIN;
+    let size = (BOX_SIZE + MARGIN * 2) as usize;
+    let mut area = Grid::new(size, size, EMPTY_COLOR);
+
+    let mut start_x = 0;
+    for y in 0..size {
+        let row = get_data_for_row(program, top + y as i128, start_x);
+        start_x = row.start_x;
+
+        for x in row.start_x..(row.start_x + row.width) {
+            let color = if x >= box_pos.x && x < box_pos.x + BOX_SIZE
+                && y as i128 >= MARGIN && (y as i128) < MARGIN + BOX_SIZE {
+                BOX_COLOR
+            } else {
+                BEAM_COLOR
+            };
+
+            if x >= left && x - left < size as i128 {
+                area.set(&Point::new((x - left) as usize, y), color);
             }
         }
-
-        println!(" ({})", rows.get(&y).unwrap().width);
-        y = y + 1;
     }
+
+    area
 }
 
-fn get_data_for_row(row_num: i128, start_x: i128) -> RowData {
+fn get_data_for_row(program: &[i128], row_num: i128, start_x: i128) -> RowData {
     let mut min_x = None;
 
     let mut current_x = start_x;
     loop {
-        let mut machine = Machine::new_from_file("src/inputs/19.txt");
+        let mut machine = Machine::new_from_memory(program.to_vec());
 
         machine.input_buffer.push_back(current_x);
         machine.input_buffer.push_back(row_num);
@@ -110,11 +163,10 @@ fn get_data_for_row(row_num: i128, start_x: i128) -> RowData {
 
         if min_x.is_none() && current_x >= 2000 {
             // Most likely an empty row
-            println!("{} {}", current_x, start_x);
             break;
         }
 
-        current_x = current_x + 1;
+        current_x += 1;
     }
 
     let mut width = 0;
@@ -130,21 +182,15 @@ fn get_data_for_row(row_num: i128, start_x: i128) -> RowData {
     }
 }
 
-fn get_box_position(rows: &HashMap<i128, RowData>, row_num: i128) -> Option<Position> {
+fn get_box_position(rows: &HashMap<i128, RowData>, row_num: i128) -> Option<Point<i128>> {
     if row_num < BOX_SIZE {
         return None;
     }
 
-    let current_row = match rows.get(&row_num) {
-        None => {return None },
-        Some(row) => row,
-    };
+    let current_row = rows.get(&row_num)?;
 
     let first_row_num = row_num - BOX_SIZE + 1;
-    let first_row = match rows.get(&first_row_num) {
-        None => {return None },
-        Some(row) => row,
-    };
+    let first_row = rows.get(&first_row_num)?;
 
     if current_row.width < BOX_SIZE {
         return None;
@@ -155,8 +201,5 @@ fn get_box_position(rows: &HashMap<i128, RowData>, row_num: i128) -> Option<Posi
         return None;
     }
 
-    return Some(Position {
-        x: current_row.start_x,
-        y: first_row_num,
-    })
+    Some(Point::new(current_row.start_x, first_row_num))
 }
\ No newline at end of file
@@ -0,0 +1,61 @@
+use crate::bench::{self, Samples};
+use crate::image::Image;
+use crate::puzzle::{self, Puzzle, Solution};
+use crate::{puzzle_01, puzzle_02, puzzle_03, puzzle_04, puzzle_05, puzzle_06, puzzle_07, puzzle_08, puzzle_09,
+            puzzle_10, puzzle_11, puzzle_12, puzzle_13, puzzle_14, puzzle_15, puzzle_16, puzzle_17, puzzle_18,
+            puzzle_19};
+
+pub struct PuzzleEntry {
+    pub day: u8,
+    pub title: &'static str,
+    pub solve: fn(&str, &[u8]) -> Solution,
+    pub bench: fn(&str, usize) -> Samples,
+    /// Draws the day's result from its input, for days that have something to show
+    pub image: Option<fn(&str) -> Image>,
+}
+
+impl PuzzleEntry {
+    fn with_image(mut self, image: fn(&str) -> Image) -> Self {
+        self.image = Some(image);
+        self
+    }
+}
+
+/// Every puzzle that has a solution, in day order
+pub fn puzzles() -> Vec<PuzzleEntry> {
+    vec![
+        entry::<puzzle_01::Day01>(1, "The Tyranny of the Rocket Equation"),
+        entry::<puzzle_02::Day02>(2, "1202 Program Alarm"),
+        entry::<puzzle_03::Day03>(3, "Crossed Wires"),
+        entry::<puzzle_04::Day04>(4, "Secure Container"),
+        entry::<puzzle_05::Day05>(5, "Sunny with a Chance of Asteroids"),
+        entry::<puzzle_06::Day06>(6, "Universal Orbit Map"),
+        entry::<puzzle_07::Day07>(7, "Amplification Circuit"),
+        entry::<puzzle_08::Day08>(8, "Space Image Format").with_image(puzzle_08::render_image),
+        entry::<puzzle_09::Day09>(9, "Sensor Boost"),
+        entry::<puzzle_10::Day10>(10, "Monitoring Station"),
+        entry::<puzzle_11::Day11>(11, "Space Police").with_image(puzzle_11::render_image),
+        entry::<puzzle_12::Day12>(12, "The N-Body Problem"),
+        entry::<puzzle_13::Day13>(13, "Care Package"),
+        entry::<puzzle_14::Day14>(14, "Space Stoichiometry"),
+        entry::<puzzle_15::Day15>(15, "Oxygen System"),
+        entry::<puzzle_16::Day16>(16, "Flawed Frequency Transmission"),
+        entry::<puzzle_17::Day17>(17, "Set and Forget").with_image(puzzle_17::render_image),
+        entry::<puzzle_18::Day18>(18, "Many-Worlds Interpretation"),
+        entry::<puzzle_19::Day19>(19, "Tractor Beam").with_image(puzzle_19::render_image),
+    ]
+}
+
+pub fn find(day: u8) -> Option<PuzzleEntry> {
+    puzzles().into_iter().find(|x| x.day == day)
+}
+
+fn entry<P: Puzzle>(day: u8, title: &'static str) -> PuzzleEntry {
+    PuzzleEntry {
+        day,
+        title,
+        solve: puzzle::solve::<P>,
+        bench: bench::measure::<P>,
+        image: None,
+    }
+}
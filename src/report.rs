@@ -0,0 +1,229 @@
+use crate::answers::Verdict;
+use crate::json;
+use crate::puzzle::{PartAnswer, PartError};
+use crate::registry::PuzzleEntry;
+use num_format::{Locale, ToFormattedString};
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum RunError {
+    UnknownDay(u8),
+    Input(String),
+    Answers(String),
+    /// Some parts failed or didn't match their known answer
+    Mismatch(usize),
+}
+
+/// Presents the results of a run as they come in
+pub trait Reporter {
+    fn day_started(&mut self, _puzzle: &PuzzleEntry) {}
+    fn part_solved(&mut self, day: u8, answer: &PartAnswer, verdict: &Verdict);
+    fn day_finished(&mut self, _day: u8, _elapsed: Duration, _verdicts: &[Verdict]) {}
+    fn run_finished(&mut self, _verdicts: &[Verdict]) {}
+    fn error(&mut self, error: &RunError);
+}
+
+/// Human readable output, with a timing table at the end when more than one day is run
+pub struct TextReporter {
+    multiple_days: bool,
+    timings: Vec<(u8, u128, String)>,
+}
+
+/// One JSON object per line, for other tools to ingest
+pub struct JsonReporter;
+
+impl RunError {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RunError::UnknownDay(_) => "unknown_day",
+            RunError::Input(_) => "input",
+            RunError::Answers(_) => "answers",
+            RunError::Mismatch(_) => "mismatch",
+        }
+    }
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::UnknownDay(day) => write!(f, "Day {} does not have a solution", day),
+            RunError::Input(message) => write!(f, "{}", message),
+            RunError::Answers(message) => write!(f, "{}", message),
+            RunError::Mismatch(count) => write!(f, "{} part(s) failed or did not match their known answer", count),
+        }
+    }
+}
+
+impl TextReporter {
+    pub fn new(multiple_days: bool) -> Self {
+        TextReporter {
+            multiple_days,
+            timings: Vec::new(),
+        }
+    }
+}
+
+impl Reporter for TextReporter {
+    fn day_started(&mut self, puzzle: &PuzzleEntry) {
+        if self.multiple_days {
+            println!("=== Day {:02}: {} ===", puzzle.day, puzzle.title);
+        }
+    }
+
+    fn part_solved(&mut self, _day: u8, answer: &PartAnswer, verdict: &Verdict) {
+        let elapsed_ms = answer.elapsed.as_millis().to_formatted_string(&Locale::en);
+        match &answer.answer {
+            Err(PartError::Panic(message)) => println!("Part {} failed ({}ms) [{}]: {}", answer.part, elapsed_ms, verdict, message),
+            Err(PartError::NotApplicable(reason)) => {
+                println!("Part {} not applicable ({}ms) [{}]: {}", answer.part, elapsed_ms, verdict, reason)
+            }
+
+
+            // Multi-line answers (e.g. rendered letters) start on their own line
+            Ok(value) if value.contains('\n') => {
+                println!("Part {} ({}ms) [{}]:", answer.part, elapsed_ms, verdict);
+                println!("{}", value);
+            }
+
+            Ok(value) => println!("Part {}: {} ({}ms) [{}]", answer.part, value, elapsed_ms, verdict),
+        }
+
+        if let Verdict::Regression { expected } = verdict {
+            if expected.contains('\n') {
+                println!("Expected:");
+                println!("{}", expected);
+            } else {
+                println!("Expected: {}", expected);
+            }
+        }
+    }
+
+    fn day_finished(&mut self, day: u8, elapsed: Duration, verdicts: &[Verdict]) {
+        let elapsed_ms = elapsed.as_millis();
+        if self.multiple_days {
+            println!();
+            let summary = verdicts.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", ");
+            self.timings.push((day, elapsed_ms, summary));
+        } else {
+            println!("Run completed in {}ms", elapsed_ms.to_formatted_string(&Locale::en));
+        }
+    }
+
+    fn run_finished(&mut self, verdicts: &[Verdict]) {
+        if self.multiple_days {
+            println!("Timings:");
+            for (day, elapsed_ms, summary) in &self.timings {
+                println!("Day {:02}: {:>10}ms  {}", day, elapsed_ms.to_formatted_string(&Locale::en), summary);
+            }
+
+            let total: u128 = self.timings.iter().map(|x| x.1).sum();
+            println!("Total:  {:>10}ms", total.to_formatted_string(&Locale::en));
+        }
+
+        let count = |matches: fn(&Verdict) -> bool| verdicts.iter().filter(|x| matches(x)).count();
+        println!("Verified: {} passed, {} regressions, {} failed, {} unverified",
+                 count(|x| *x == Verdict::Pass),
+                 count(|x| matches!(x, Verdict::Regression { .. })),
+                 count(|x| matches!(x, Verdict::Fail { .. })),
+                 count(|x| *x == Verdict::Unverified));
+    }
+
+    fn error(&mut self, error: &RunError) {
+        eprintln!("{}", error);
+    }
+}
+
+impl Reporter for JsonReporter {
+    fn part_solved(&mut self, day: u8, answer: &PartAnswer, verdict: &Verdict) {
+        println!("{}", part_record(day, answer, verdict));
+    }
+
+    fn error(&mut self, error: &RunError) {
+        println!("{}", error_record(error));
+    }
+}
+
+/// `{"day": .., "part": .., "answer": .., "elapsed_ms": .., "status": ..}`, with an `error` object
+/// instead of the answer when the part failed and the `expected` answer for regressions
+pub fn part_record(day: u8, answer: &PartAnswer, verdict: &Verdict) -> String {
+    let mut fields = vec![
+        format!("\"day\":{}", day),
+        format!("\"part\":{}", answer.part),
+    ];
+
+    match &answer.answer {
+        Ok(value) => fields.push(format!("\"answer\":{}", json::string(value))),
+        Err(PartError::Panic(message)) => {
+            fields.push(format!("\"error\":{{\"kind\":\"panic\",\"message\":{}}}", json::string(message)))
+        }
+
+        Err(PartError::NotApplicable(reason)) => {
+            fields.push(format!("\"error\":{{\"kind\":\"not_applicable\",\"message\":{}}}", json::string(reason)))
+        }
+
+    }
+
+    fields.push(format!("\"elapsed_ms\":{:.3}", answer.elapsed.as_secs_f64() * 1000.0));
+    fields.push(format!("\"status\":{}", json::string(&verdict.to_string().to_lowercase())));
+    if let Verdict::Regression { expected } = verdict {
+        fields.push(format!("\"expected\":{}", json::string(expected)));
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+/// `{"error": {"kind": .., "message": ..}}`, plus the day when the error is about a single day
+pub fn error_record(error: &RunError) -> String {
+    let day = match error {
+        RunError::UnknownDay(day) => format!("\"day\":{},", day),
+        _ => String::new(),
+    };
+
+    format!("{{{}\"error\":{{\"kind\":{},\"message\":{}}}}}",
+            day, json::string(error.kind()), json::string(&error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(part: u8, answer: Result<&str, &str>) -> PartAnswer {
+        PartAnswer {
+            part,
+            answer: answer.map(|x| x.to_owned()).map_err(|x| PartError::Panic(x.to_owned())),
+            elapsed: Duration::from_micros(1500),
+        }
+    }
+
+    #[test]
+    fn part_records() {
+        assert_eq!(part_record(7, &answer(2, Ok("4275738")), &Verdict::Pass),
+                   "{\"day\":7,\"part\":2,\"answer\":\"4275738\",\"elapsed_ms\":1.500,\"status\":\"pass\"}");
+
+        assert_eq!(part_record(8, &answer(2, Ok("# #\n #")), &Verdict::Regression { expected: "#".to_owned() }),
+                   "{\"day\":8,\"part\":2,\"answer\":\"# #\\n #\",\"elapsed_ms\":1.500,\"status\":\"regression\",\"expected\":\"#\"}");
+
+        let message = "Invalid opcode 99";
+        let verdict = Verdict::Fail { message: message.to_owned() };
+        assert_eq!(part_record(5, &answer(1, Err(message)), &verdict),
+                   "{\"day\":5,\"part\":1,\"error\":{\"kind\":\"panic\",\"message\":\"Invalid opcode 99\"},\"elapsed_ms\":1.500,\"status\":\"fail\"}");
+
+        let skipped = PartAnswer {
+            part: 2,
+            answer: Err(PartError::NotApplicable("Too small".to_owned())),
+            elapsed: Duration::from_micros(1500),
+        };
+
+        assert_eq!(part_record(18, &skipped, &Verdict::Unverified),
+                   "{\"day\":18,\"part\":2,\"error\":{\"kind\":\"not_applicable\",\"message\":\"Too small\"},\"elapsed_ms\":1.500,\"status\":\"unverified\"}");
+    }
+
+    #[test]
+    fn error_records() {
+        assert_eq!(error_record(&RunError::UnknownDay(24)),
+                   "{\"day\":24,\"error\":{\"kind\":\"unknown_day\",\"message\":\"Day 24 does not have a solution\"}}");
+        assert_eq!(error_record(&RunError::Input("Could not read x".to_owned())),
+                   "{\"error\":{\"kind\":\"input\",\"message\":\"Could not read x\"}}");
+    }
+}
@@ -0,0 +1,216 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Fewest steps from the start to every node a breadth first search reached, remembering how
+/// each node was first reached so paths can be rebuilt
+#[derive(Debug, Clone)]
+pub struct Distances<N> {
+    start: N,
+    steps: HashMap<N, usize>,
+    parents: HashMap<N, N>,
+}
+
+/// The cheapest route found between two nodes, including both ends
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Path<N> {
+    pub cost: usize,
+    pub nodes: Vec<N>,
+}
+
+impl<N: Clone + Eq + Hash> Distances<N> {
+    pub fn get(&self, node: &N) -> Option<usize> {
+        self.steps.get(node).copied()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.steps.contains_key(node)
+    }
+
+    /// Steps to the node furthest from the start
+    pub fn max(&self) -> usize {
+        self.steps.values().copied().max().unwrap_or(0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&N, usize)> {
+        self.steps.iter().map(|(node, steps)| (node, *steps))
+    }
+
+    /// Nodes from the start to the target, or `None` if the target was never reached
+    pub fn path_to(&self, target: &N) -> Option<Vec<N>> {
+        if !self.contains(target) {
+            return None;
+        }
+
+        Some(rebuild_path(&self.start, target, &self.parents))
+    }
+}
+
+/// Breadth first search over every node reachable from the start
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> Distances<N>
+    where N: Clone + Eq + Hash, F: FnMut(&N) -> I, I: IntoIterator<Item = N> {
+    let mut steps = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    steps.insert(start.clone(), 0);
+    queue.push_back(start.clone());
+    while let Some(node) = queue.pop_front() {
+        let next_steps = steps[&node] + 1;
+        for neighbor in neighbors(&node) {
+            if steps.contains_key(&neighbor) {
+                continue;
+            }
+
+            steps.insert(neighbor.clone(), next_steps);
+            parents.insert(neighbor.clone(), node.clone());
+            queue.push_back(neighbor);
+        }
+    }
+
+    Distances { start, steps, parents }
+}
+
+/// Every node reachable from the start, including the start itself
+pub fn flood_fill<N, F, I>(start: N, mut neighbors: F) -> HashSet<N>
+    where N: Clone + Eq + Hash, F: FnMut(&N) -> I, I: IntoIterator<Item = N> {
+    let mut found = HashSet::new();
+    let mut pending = vec![start.clone()];
+    found.insert(start);
+
+    while let Some(node) = pending.pop() {
+        for neighbor in neighbors(&node) {
+            if found.insert(neighbor.clone()) {
+                pending.push(neighbor);
+            }
+        }
+    }
+
+    found
+}
+
+/// Cheapest path from the start to the first node passing `is_goal`, where `neighbors` returns
+/// each node reachable from a node along with the cost of moving there
+pub fn dijkstra<N, F, I, G>(start: N, neighbors: F, is_goal: G) -> Option<Path<N>>
+    where N: Clone + Eq + Hash, F: FnMut(&N) -> I, I: IntoIterator<Item = (N, usize)>, G: Fn(&N) -> bool {
+    astar(start, neighbors, |_| 0, is_goal)
+}
+
+/// Dijkstra's search guided by a heuristic, which must never overestimate the remaining cost
+/// for the returned path to be the cheapest
+pub fn astar<N, F, I, H, G>(start: N, mut neighbors: F, heuristic: H, is_goal: G) -> Option<Path<N>>
+    where N: Clone + Eq + Hash, F: FnMut(&N) -> I, I: IntoIterator<Item = (N, usize)>,
+          H: Fn(&N) -> usize, G: Fn(&N) -> bool {
+    // The heap refers to nodes by index, so nodes don't need to be ordered themselves
+    let mut nodes = vec![start.clone()];
+    let mut costs = HashMap::new();
+    let mut parents = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    costs.insert(start.clone(), 0);
+    queue.push(Reverse((heuristic(&start), 0, 0)));
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let node = nodes[index].clone();
+        if costs.get(&node).map(|x| *x < cost).unwrap_or(false) {
+            continue; // Already reached this node more cheaply
+        }
+
+        if is_goal(&node) {
+            let nodes = rebuild_path(&start, &node, &parents);
+            return Some(Path { cost, nodes });
+        }
+
+        for (neighbor, move_cost) in neighbors(&node) {
+            let new_cost = cost + move_cost;
+            if costs.get(&neighbor).map(|x| *x <= new_cost).unwrap_or(false) {
+                continue;
+            }
+
+            costs.insert(neighbor.clone(), new_cost);
+            parents.insert(neighbor.clone(), node.clone());
+            queue.push(Reverse((new_cost + heuristic(&neighbor), new_cost, nodes.len())));
+            nodes.push(neighbor);
+        }
+    }
+
+    None
+}
+
+fn rebuild_path<N: Clone + Eq + Hash>(start: &N, target: &N, parents: &HashMap<N, N>) -> Vec<N> {
+    let mut path = vec![target.clone()];
+    let mut current = target;
+    while current != start {
+        current = &parents[current];
+        path.push(current.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Grid, Point};
+
+    const MAZE: &str = "\
+..#....
+.##.##.
+...#...
+.#...#.";
+
+    fn open_neighbors(grid: &Grid<bool>, point: &Point<usize>) -> Vec<Point<usize>> {
+        grid.neighbors(point).into_iter().filter(|x| *grid.get(x).unwrap()).collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_paths() {
+        let grid = Grid::parse(MAZE, |ch| ch == '.');
+        let distances = bfs(Point::new(0, 0), |x| open_neighbors(&grid, x));
+
+        assert_eq!(distances.get(&Point::new(6, 0)), Some(12));
+        assert_eq!(distances.get(&Point::new(2, 0)), None);
+        assert_eq!(distances.max(), 16);
+
+        let path = distances.path_to(&Point::new(2, 3)).unwrap();
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.first(), Some(&Point::new(0, 0)));
+        assert_eq!(path.last(), Some(&Point::new(2, 3)));
+    }
+
+    #[test]
+    fn flood_fill_finds_regions() {
+        let grid = Grid::parse(MAZE, |ch| ch == '.');
+        assert_eq!(flood_fill(Point::new(3, 1), |x| open_neighbors(&grid, x)).len(), 20);
+
+        let grid = Grid::parse("..#..", |ch| ch == '.');
+        assert_eq!(flood_fill(Point::new(0, 0), |x| open_neighbors(&grid, x)).len(), 2);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_edges() {
+        let edges = |node: &char| match node {
+            'a' => vec![('b', 1), ('c', 5)],
+            'b' => vec![('c', 1), ('d', 7)],
+            'c' => vec![('d', 2)],
+            _ => vec![],
+        };
+
+        let path = dijkstra('a', edges, |x| *x == 'd').unwrap();
+        assert_eq!(path, Path { cost: 4, nodes: vec!['a', 'b', 'c', 'd'] });
+        assert_eq!(dijkstra('d', edges, |x| *x == 'a'), None);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = Grid::parse(MAZE, |ch| ch == '.');
+        let goal = Point::new(6, 3);
+        let neighbors = |x: &Point<usize>| open_neighbors(&grid, x).into_iter().map(|x| (x, 1));
+
+        let guided = astar(Point::new(0, 0), neighbors, |x| x.manhattan_distance(&goal), |x| *x == goal).unwrap();
+        let unguided = dijkstra(Point::new(0, 0), neighbors, |x| *x == goal).unwrap();
+        assert_eq!(guided.cost, 11);
+        assert_eq!(guided.cost, unguided.cost);
+        assert_eq!(guided.nodes.len(), 12);
+    }
+}